@@ -0,0 +1,45 @@
+//! Compares key lookups through [Localizable::index] with the linear [Localizable::translations_for_key].
+//! Run with `cargo bench --bench index`.
+
+use std::hint::black_box;
+use std::time::Instant;
+use swift_localizable_json_parser::types::output::{
+    Localizable, LocalizationValue, SingleTranslation,
+};
+
+const KEYS: usize = 5_000;
+
+fn main() {
+    let localizable = Localizable {
+        source_language: "en".to_string(),
+        single_translation: (0..KEYS)
+            .map(|i| SingleTranslation {
+                key_raw: format!("key_{i}"),
+                key_alphanumeric: format!("key_{i}"),
+                localization_value: LocalizationValue::default(),
+                comment: String::new(),
+                should_translate: true,
+                substitutions: Default::default(),
+            })
+            .collect(),
+        version: "1.0".to_string(),
+    };
+    let keys: Vec<_> = (0..KEYS).map(|i| format!("key_{i}")).collect();
+
+    let start = Instant::now();
+
+    for key in &keys {
+        black_box(localizable.translations_for_key(key));
+    }
+
+    println!("linear: {:?}", start.elapsed());
+
+    let start = Instant::now();
+    let index = localizable.build_index();
+
+    for key in &keys {
+        black_box(index.get(key));
+    }
+
+    println!("index (including build): {:?}", start.elapsed());
+}
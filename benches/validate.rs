@@ -0,0 +1,47 @@
+//! Compares a validation-only run with a full Android conversion of the same file.
+//! The validators work on the [Localizable] tree directly, so they should be far cheaper.
+//! Run with `cargo bench --bench validate`.
+
+use std::hint::black_box;
+use std::time::Instant;
+use swift_localizable_json_parser::parse_from_bytes;
+use swift_localizable_json_parser::types::output::Localizable;
+
+const RUNS: usize = 200;
+
+fn validate(localizable: &Localizable) {
+    black_box(localizable.control_characters());
+    black_box(localizable.mixed_translation_kinds());
+    black_box(localizable.plural_type_conflicts());
+    black_box(localizable.raw_key_in_translation());
+    black_box(localizable.whitespace_only_value_variants());
+    black_box(localizable.positional_specifier_issues());
+}
+
+fn main() {
+    let localizable = parse_from_bytes(include_bytes!("../test_resources/Localizable.xcstrings"))
+        .unwrap()
+        .localizable;
+
+    let start = Instant::now();
+
+    for _ in 0..RUNS {
+        validate(&localizable);
+    }
+
+    println!("validate only: {:?}", start.elapsed());
+
+    let start = Instant::now();
+
+    for _ in 0..RUNS {
+        black_box(
+            localizable
+                .localized_per_language()
+                .localized_for_android(Default::default())
+                .unwrap(),
+        );
+        validate(&localizable);
+    }
+
+    println!("conversion + validate: {:?}", start.elapsed());
+}
@@ -1,28 +1,306 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use crate::prelude::*;
+use alloc::collections::BTreeMap;
 use serde::{Serialize, Serializer};
-use std::collections::{BTreeMap, HashMap};
+#[cfg(feature = "std")]
 use std::path::PathBuf;
 
-use crate::types::output::ParsedResult;
+use crate::types::output::{ParseConfig, ParsedResult};
+
+/// The map used for the deserialized input, without `std` there is no `HashMap` available
+#[cfg(feature = "std")]
+pub type Map<K, V> = std::collections::HashMap<K, V>;
+#[cfg(not(feature = "std"))]
+pub type Map<K, V> = BTreeMap<K, V>;
+
+/// The parts of the std prelude that live in alloc, so the core compiles without `std`
+#[allow(unused_imports)]
+mod prelude {
+    pub use alloc::borrow::ToOwned;
+    pub use alloc::boxed::Box;
+    pub use alloc::string::{String, ToString};
+    pub use alloc::vec::Vec;
+    pub use alloc::{format, vec};
+}
+
+/// Binds `$name` to a compiled regex. With `std` it's compiled once, without it on every call.
+macro_rules! cached_regex {
+    ($name: ident, $pattern: expr) => {
+        #[cfg(feature = "std")]
+        let $name: &regex::Regex = {
+            static REGEX: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+
+            REGEX.get_or_init(|| regex::Regex::new($pattern).unwrap())
+        };
+        #[cfg(not(feature = "std"))]
+        let $name: &regex::Regex = &regex::Regex::new($pattern).unwrap();
+    };
+}
 
 pub const TRANSLATED_STATE: &str = "translated";
 pub const NEW_STATE: &str = "new";
+pub const NEEDS_REVIEW_STATE: &str = "needs_review";
+/// Used when a file has no `sourceLanguage`, see [ParseConfig::default_source_language]
+pub const DEFAULT_SOURCE_LANGUAGE: &str = "en";
+/// Environment variable supplying the source language for files without `sourceLanguage`
+pub const SOURCE_LANGUAGE_ENV: &str = "LOCALIZABLE_SOURCE_LANGUAGE";
+/// Marks comments written by this crate, see [types::output::Localizable::set_generated_comment]
+pub const DEFAULT_AUTO_COMMENT_PREFIX: &str = "[auto]";
 
 pub fn parse_from_string(raw: String) -> ParsedResult {
-    parse::from_string(raw)
+    parse_from_string_with_config(raw, &ParseConfig::default())
 }
 
 pub fn parse_from_bytes(raw: &[u8]) -> ParsedResult {
-    parse_from_string(String::from_utf8(raw.to_vec())?)
+    parse_from_bytes_with_config(raw, &ParseConfig::default())
 }
 
+#[cfg(feature = "std")]
 pub fn parse_from_file(file: &PathBuf) -> ParsedResult {
-    parse_from_string(std::fs::read_to_string(file)?)
+    parse_from_file_with_config(file, &ParseConfig::default())
+}
+
+pub fn parse_from_string_with_config(raw: String, config: &ParseConfig) -> ParsedResult {
+    parse::from_string(raw, config)
+}
+
+pub fn parse_from_bytes_with_config(raw: &[u8], config: &ParseConfig) -> ParsedResult {
+    parse_from_string_with_config(String::from_utf8(raw.to_vec())?, config)
+}
+
+/// The result of [parse_from_string_lenient]
+#[derive(Debug, Clone)]
+pub struct LenientParsed {
+    pub parsed: types::output::Parsed,
+    /// The keys of the `strings` entries which failed to deserialize, with the error
+    pub skipped: Vec<(String, String)>,
+}
+
+/// Like [parse_from_string_with_config], but entries of `strings` which fail to deserialize are
+/// skipped instead of failing the whole file. Useful to recover a slightly corrupt file.
+pub fn parse_from_string_lenient(
+    raw: String,
+    config: &ParseConfig,
+) -> Result<LenientParsed, types::output::ParsedError> {
+    let mut value: serde_json::Value = serde_json::from_str(&raw)?;
+    let text = |value: &serde_json::Value, field: &str| {
+        value
+            .get(field)
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+    let source_language = text(&value, "sourceLanguage");
+    let version = text(&value, "version");
+    let mut strings = Map::default();
+    let mut skipped = vec![];
+
+    if let Some(serde_json::Value::Object(entries)) = value.get_mut("strings").map(core::mem::take)
+    {
+        for (key, entry) in entries {
+            match serde_json::from_value(entry) {
+                Ok(language) => {
+                    strings.insert(key, language);
+                }
+                Err(error) => {
+                    log::warn!("Skipping malformed entry {}: {}", key, error);
+
+                    skipped.push((key, error.to_string()));
+                }
+            }
+        }
+    }
+
+    skipped.sort();
+
+    let translation = types::input::Translation {
+        source_language,
+        strings,
+        version,
+    };
+
+    Ok(LenientParsed {
+        parsed: parse::from_translation(translation, config)?,
+        skipped,
+    })
+}
+
+/// Timings and counts of a single parse, see [parse_from_string_with_metrics]
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default)]
+pub struct ParseMetrics {
+    /// Time spent deserializing the JSON
+    pub deserialize_time: std::time::Duration,
+    /// Time spent building the [types::output::Localizable] from the deserialized JSON
+    pub convert_time: std::time::Duration,
+    pub key_count: usize,
+    pub language_count: usize,
+}
+
+/// Same as [parse_from_string_with_config], but also measures the parse. This is a separate
+/// entry point so the other parse functions don't pay for the measuring.
+#[cfg(feature = "std")]
+pub fn parse_from_string_with_metrics(
+    raw: String,
+    config: &ParseConfig,
+) -> Result<(types::output::Parsed, ParseMetrics), types::output::ParsedError> {
+    let start = std::time::Instant::now();
+    let translation = serde_json::from_str(&raw)?;
+    let deserialize_time = start.elapsed();
+    let start = std::time::Instant::now();
+    let parsed = parse::from_translation(translation, config)?;
+    let metrics = ParseMetrics {
+        deserialize_time,
+        convert_time: start.elapsed(),
+        key_count: parsed.localizable.single_translation.len(),
+        language_count: parsed.localizable.languages().len(),
+    };
+
+    log::debug!("Parse metrics: {:?}", metrics);
+
+    Ok((parsed, metrics))
+}
+
+#[cfg(feature = "std")]
+pub fn parse_from_file_with_config(file: &PathBuf, config: &ParseConfig) -> ParsedResult {
+    let mut parsed = parse_from_string_with_config(std::fs::read_to_string(file)?, config)?;
+
+    parsed.table_name = file
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string());
+
+    Ok(parsed)
+}
+
+/// The result of [convert_directory]
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+pub struct DirectoryConversion {
+    /// The converted catalogs, sorted by path
+    pub converted: Vec<(PathBuf, types::output::LocalizedForAndroid)>,
+    /// The catalogs that failed to parse or write, these don't abort the rest of the batch
+    pub errors: Vec<(PathBuf, types::output::ParsedError)>,
+}
+
+/// Parses every `*.xcstrings` file in `input_dir` and converts it for Android. When the
+/// [types::output::AndroidWriteConfig] has no file or table name, every catalog is written to a
+/// file named after its table, so the catalogs don't overwrite each other's `strings.xml`.
+#[cfg(feature = "std")]
+pub fn convert_directory(
+    input_dir: &PathBuf,
+    config: types::output::AndroidLocalizeConfig,
+) -> Result<DirectoryConversion, types::output::ParsedError> {
+    let mut files = vec![];
+
+    for entry in std::fs::read_dir(input_dir)? {
+        let path = entry?.path();
+
+        if path.is_file() && path.extension().is_some_and(|e| e == "xcstrings") {
+            files.push(path);
+        }
+    }
+
+    files.sort();
+
+    let mut conversion = DirectoryConversion::default();
+
+    for file in files {
+        let converted = parse_from_file(&file).and_then(|parsed| {
+            let mut config = config.clone();
+
+            if let Some(write_config) = &mut config.write_config {
+                if write_config.file_name.is_none() && write_config.table_name.is_none() {
+                    write_config.table_name = parsed.table_name.clone();
+                }
+            }
+
+            parsed
+                .localizable
+                .localized_per_language()
+                .localized_for_android(config)
+        });
+
+        match converted {
+            Ok(localized) => conversion.converted.push((file, localized)),
+            Err(error) => {
+                log::warn!("Failed to convert {}: {}", file.display(), error);
+
+                conversion.errors.push((file, error))
+            }
+        }
+    }
+
+    Ok(conversion)
+}
+
+/// Conversion settings which can be versioned as a JSON file, see [run_with_config]
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct ConversionConfig {
+    pub source_file: PathBuf,
+    /// The `res` directory the `values-xx` directories are written in
+    pub output_dir: PathBuf,
+    pub app_name: String,
+    /// Only these languages are converted, empty means every language
+    pub languages: Vec<String>,
+    pub xml_declaration: bool,
+    pub whitespace: types::output::WhitespaceHandling,
+    pub directory_casing: types::output::DirectoryCasing,
+    pub strict_placeholders: bool,
+    pub tools_locale: bool,
+}
+
+#[cfg(feature = "std")]
+impl ConversionConfig {
+    pub fn from_json(raw: &str) -> Result<Self, types::output::ParsedError> {
+        serde_json::from_str(raw)
+            .map_err(|e| types::output::ParsedError::InvalidConfig(e.to_string()))
+    }
+
+    pub fn from_file(file: &PathBuf) -> Result<Self, types::output::ParsedError> {
+        Self::from_json(&std::fs::read_to_string(file)?)
+    }
+}
+
+/// Parses [ConversionConfig::source_file] and writes the Android resources in
+/// [ConversionConfig::output_dir]
+#[cfg(feature = "std")]
+pub fn run_with_config(
+    config: &ConversionConfig,
+) -> Result<types::output::LocalizedForAndroid, types::output::ParsedError> {
+    let parsed = parse_from_file(&config.source_file)?;
+    let mut localized_per_language = parsed.localizable.localized_per_language();
+
+    if !config.languages.is_empty() {
+        localized_per_language
+            .language_localized
+            .retain(|language, _| config.languages.contains(language));
+    }
+
+    localized_per_language.localized_for_android(types::output::AndroidLocalizeConfig {
+        app_name: config.app_name.to_string(),
+        xml_declaration: config.xml_declaration,
+        whitespace: config.whitespace,
+        strict_placeholders: config.strict_placeholders,
+        tools_locale: config.tools_locale,
+        write_config: Some(types::output::AndroidWriteConfig {
+            write_in: config.output_dir.clone(),
+            directory_casing: config.directory_casing,
+            table_name: parsed.table_name,
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
 }
 
 /// https://stackoverflow.com/a/42723390/7715250
 /// For use with serde's [serialize_with] attribute
 fn ordered_map<S, K: Ord + Serialize, V: Serialize>(
-    value: &HashMap<K, V>,
+    value: &Map<K, V>,
     serializer: S,
 ) -> Result<S::Ok, S::Error>
 where
@@ -33,20 +311,54 @@ where
 }
 
 mod parse {
-    use crate::types::inoutoutput::TranslationValue;
-    use crate::types::input::{Translation, TranslationTypeContainer};
+    use crate::prelude::*;
+    use crate::types::inoutoutput::{DeviceValue, Plural, StringUnitContainer, TranslationValue};
+    use crate::types::input::{Substitution, Translation, TranslationTypeContainer};
     use crate::types::output::{
-        Localizable, LocalizationValue, Parsed, ParsedError, ParsedResult, PluralVariate,
-        SinglePluralVariation, SingleTranslation,
+        DeviceVariate, Localizable, LocalizationValue, ParseConfig, Parsed, ParsedError,
+        ParsedResult, PluralVariate, SingleDeviceVariation, SinglePluralVariation,
+        SingleTranslation,
     };
     use crate::TRANSLATED_STATE;
-    use regex::Regex;
+    use alloc::collections::BTreeMap;
+
+    pub(crate) fn from_string(translations: String, config: &ParseConfig) -> ParsedResult {
+        from_translation(serde_json::from_str(&translations)?, config)
+    }
+
+    pub(crate) fn from_translation(
+        mut translation: Translation,
+        config: &ParseConfig,
+    ) -> ParsedResult {
+        if translation.source_language.is_empty() {
+            translation.source_language = config.resolve_default_source_language();
+        }
+
+        // The output tree always holds the Apple state names, whatever the file uses
+        let unit = |value: &TranslationValue| {
+            let mut cleaned = if config.strip_control_characters {
+                value
+                    .value
+                    .chars()
+                    .filter(|c| !crate::validate::is_disallowed_control_character(*c))
+                    .collect()
+            } else {
+                value.value.clone()
+            };
+
+            if let Some(max) = config.max_consecutive_newlines {
+                cleaned = collapse_newlines(&cleaned, max);
+            }
 
-    pub(crate) fn from_string(translations: String) -> ParsedResult {
-        let translation: Translation = serde_json::from_str(&translations)?;
+            TranslationValue {
+                state: config.state_names.normalize(&value.state),
+                value: cleaned,
+            }
+        };
         let mut localizable = Localizable {
             source_language: translation.source_language.clone(),
             single_translation: vec![],
+            version: translation.version.clone(),
         };
 
         for (key, language) in &translation.strings {
@@ -55,11 +367,81 @@ mod parse {
             }
 
             let mut localization_value = LocalizationValue::default();
+            let mut substitutions = BTreeMap::new();
 
             for (language, translation_type_container) in &language.localizations {
+                if config.strict_states {
+                    for state in container_states(translation_type_container) {
+                        if config.state_names.state(state).is_none() {
+                            log::error!("Unknown state {} for {} ({})", state, key, language);
+
+                            return Err(ParsedError::UnknownState(state.to_string()));
+                        }
+                    }
+                }
+
                 let translation = match translation_type_container {
                     TranslationTypeContainer::StringUnit(su) => {
-                        crate::types::output::Translation::Localization(su.string_unit.clone())
+                        if !su.substitutions.is_empty() {
+                            substitutions.insert(
+                                language.to_string(),
+                                su.substitutions
+                                    .iter()
+                                    .map(|(name, substitution)| {
+                                        (name.to_string(), output_substitution(substitution, &unit))
+                                    })
+                                    .collect(),
+                            );
+                        }
+
+                        crate::types::output::Translation::Localization(unit(&su.string_unit))
+                    }
+                    TranslationTypeContainer::Variation(container)
+                        if container.variations.plural.is_empty()
+                            && container.variations.device.is_some() =>
+                    {
+                        let device = container.variations.device.clone().unwrap_or_default();
+                        let mut variations: Vec<_> = Default::default();
+
+                        for (field, variate) in [
+                            (&device.iphone, DeviceVariate::IPhone),
+                            (&device.ipad, DeviceVariate::IPad),
+                            (&device.mac, DeviceVariate::Mac),
+                            (&device.appletv, DeviceVariate::AppleTv),
+                            (&device.watch, DeviceVariate::Watch),
+                            (&device.applevision, DeviceVariate::AppleVision),
+                            (&device.other, DeviceVariate::Other),
+                        ] {
+                            match field {
+                                Some(DeviceValue::StringUnit(o)) => {
+                                    variations.push(SingleDeviceVariation {
+                                        variate,
+                                        translation_value: unit(&o.string_unit),
+                                    });
+                                }
+                                Some(DeviceValue::Variation(_)) => {
+                                    return Err(ParsedError::UnsupportedVariation(format!(
+                                        "{key} ({language}): a variation per device"
+                                    )));
+                                }
+                                None => {}
+                            }
+                        }
+
+                        if variations.is_empty() {
+                            return Err(ParsedError::UnsupportedVariation(format!(
+                                "{key} ({language}): an empty device variation"
+                            )));
+                        }
+
+                        crate::types::output::Translation::DeviceVariation(variations)
+                    }
+                    TranslationTypeContainer::Variation(container)
+                        if container.variations.plural.is_empty() =>
+                    {
+                        return Err(ParsedError::UnsupportedVariation(format!(
+                            "{key} ({language}): no plural or device variation"
+                        )));
                     }
                     TranslationTypeContainer::Variation(container) => {
                         let v = &container.variations;
@@ -70,7 +452,7 @@ mod parse {
                                 if let Some(o) = &$field {
                                     variations.push(SinglePluralVariation {
                                         variate: $variate,
-                                        translation_value: o.string_unit.clone(),
+                                        translation_value: unit(&o.string_unit),
                                     });
                                 }
                             };
@@ -92,10 +474,9 @@ mod parse {
                     .insert(language.to_string(), translation);
             }
 
-            if localization_value
+            if !localization_value
                 .language_translation
-                .get(&translation.source_language)
-                .is_none()
+                .contains_key(&translation.source_language)
             {
                 localization_value
                     .language_translation
@@ -109,18 +490,21 @@ mod parse {
                     );
             }
 
-            // Replace any non-alphanumeric value with a _
-            let re = Regex::new(r"[^a-zA-Z0-9]+").unwrap();
-            let sanitized_android_key = re
-                .replace_all(key.trim(), "_")
-                .trim_matches('_')
-                .to_lowercase();
+            let mut sanitized_android_key = crate::android::resource_name(key);
+
+            if let Some(prefix) = &config.leading_digit_prefix {
+                if sanitized_android_key.starts_with(|c: char| c.is_ascii_digit()) {
+                    sanitized_android_key.insert_str(0, prefix);
+                }
+            }
 
             localizable.single_translation.push(SingleTranslation {
                 key_raw: key.to_string(),
                 key_alphanumeric: sanitized_android_key,
                 localization_value,
-                comment: language.comment.to_string(),
+                comment: strip_comment_prefix(&language.comment, config),
+                should_translate: language.should_translate.unwrap_or(true),
+                substitutions,
             });
         }
 
@@ -131,486 +515,9409 @@ mod parse {
         Ok(Parsed {
             localizable,
             translation,
+            table_name: None,
         })
     }
-}
 
-pub mod types {
-    pub mod inoutoutput {
-        use serde::{Deserialize, Serialize};
+    fn output_substitution(
+        substitution: &Substitution,
+        unit: &impl Fn(&TranslationValue) -> TranslationValue,
+    ) -> crate::types::output::Substitution {
+        let plural = &substitution.variations.plural;
 
-        #[derive(Serialize, Debug, Deserialize, Clone, Default)]
-        pub struct TranslationValue {
-            pub state: String,
-            pub value: String,
+        crate::types::output::Substitution {
+            arg_num: substitution.arg_num,
+            format_specifier: substitution.format_specifier.to_string(),
+            plural: [
+                (&plural.zero, PluralVariate::Zero),
+                (&plural.one, PluralVariate::One),
+                (&plural.two, PluralVariate::Two),
+                (&plural.few, PluralVariate::Few),
+                (&plural.many, PluralVariate::Many),
+                (&plural.other, PluralVariate::Other),
+            ]
+            .into_iter()
+            .filter_map(|(field, variate)| {
+                field.as_ref().map(|o| SinglePluralVariation {
+                    variate,
+                    translation_value: unit(&o.string_unit),
+                })
+            })
+            .collect(),
         }
+    }
 
-        #[derive(Debug, Serialize, Deserialize, Clone, Default)]
-        pub struct Variation {
-            pub plural: Plural,
-        }
+    /// Shortens runs of more than `max` newlines to `max` newlines, `max` is at least 1
+    fn collapse_newlines(value: &str, max: usize) -> String {
+        let max = max.max(1);
+        let mut collapsed = String::with_capacity(value.len());
+        let mut run = 0;
 
-        #[derive(Serialize, Deserialize, Debug, Clone, Default)]
-        pub struct StringUnitContainer {
-            #[serde(rename = "stringUnit")]
-            pub string_unit: TranslationValue,
+        for c in value.chars() {
+            if c == '\n' {
+                run += 1;
+
+                if run > max {
+                    continue;
+                }
+            } else {
+                run = 0;
+            }
+
+            collapsed.push(c);
         }
 
-        #[derive(Debug, Serialize, Deserialize, Clone, Default)]
-        pub struct Plural {
-            #[serde(skip_serializing_if = "Option::is_none")]
-            pub zero: Option<StringUnitContainer>,
-            #[serde(skip_serializing_if = "Option::is_none")]
-            pub one: Option<StringUnitContainer>,
-            #[serde(skip_serializing_if = "Option::is_none")]
-            pub two: Option<StringUnitContainer>,
-            #[serde(skip_serializing_if = "Option::is_none")]
-            pub other: Option<StringUnitContainer>,
-            #[serde(skip_serializing_if = "Option::is_none")]
-            pub many: Option<StringUnitContainer>,
-            #[serde(skip_serializing_if = "Option::is_none")]
-            pub few: Option<StringUnitContainer>,
+        collapsed
+    }
+
+    fn strip_comment_prefix(comment: &str, config: &ParseConfig) -> String {
+        match &config.strip_comment_prefix {
+            Some(prefix) => match comment.strip_prefix(prefix.as_str()) {
+                Some(stripped) => stripped.trim_start().to_string(),
+                None => comment.to_string(),
+            },
+            None => comment.to_string(),
         }
     }
-    pub mod input {
-        use std::collections::HashMap;
 
-        use crate::ordered_map;
-        use crate::types::inoutoutput::{StringUnitContainer, TranslationValue, Variation};
-        use serde::{Deserialize, Serialize};
+    fn container_states(container: &TranslationTypeContainer) -> Vec<&str> {
+        match container {
+            TranslationTypeContainer::StringUnit(su) => unit_states(su),
+            TranslationTypeContainer::Variation(container) => {
+                let mut states = plural_states(&container.variations.plural);
 
-        #[derive(Debug, Serialize, Deserialize, Clone)]
-        pub struct Translation {
-            #[allow(dead_code)]
-            #[serde(rename = "sourceLanguage")]
-            pub source_language: String,
-            #[serde(serialize_with = "ordered_map")]
-            pub strings: HashMap<String, Language>,
-            #[allow(dead_code)]
-            pub version: String,
-        }
+                if let Some(device) = &container.variations.device {
+                    for value in [
+                        &device.iphone,
+                        &device.ipad,
+                        &device.mac,
+                        &device.appletv,
+                        &device.watch,
+                        &device.applevision,
+                        &device.other,
+                    ]
+                    .into_iter()
+                    .flatten()
+                    {
+                        match value {
+                            DeviceValue::StringUnit(unit) => states.extend(unit_states(unit)),
+                            DeviceValue::Variation(nested) => {
+                                states.extend(plural_states(&nested.variations.plural))
+                            }
+                        }
+                    }
+                }
 
-        #[derive(Debug, Serialize, Deserialize, Clone)]
-        #[serde(untagged)]
-        #[allow(clippy::large_enum_variant)]
-        pub enum TranslationType {
-            StringUnit(TranslationValue),
-            Variation(Variation),
+                states
+            }
         }
+    }
 
-        #[derive(Debug, Serialize, Deserialize, Clone, Default)]
-        pub struct VariationContainer {
-            pub variations: Variation,
-        }
+    /// The state of the unit and of the plural units of its substitutions
+    fn unit_states(unit: &StringUnitContainer) -> Vec<&str> {
+        let mut states = vec![unit.string_unit.state.as_str()];
 
-        #[derive(Debug, Serialize, Deserialize, Clone)]
-        #[serde(untagged)]
-        #[allow(clippy::large_enum_variant)]
-        pub enum TranslationTypeContainer {
-            StringUnit(StringUnitContainer),
-            Variation(VariationContainer),
+        for substitution in unit.substitutions.values() {
+            states.extend(plural_states(&substitution.variations.plural));
         }
 
-        #[derive(Debug, Serialize, Deserialize, Clone)]
-        pub struct Language {
-            #[serde(default, skip_serializing_if = "String::is_empty")]
-            pub comment: String,
-            #[serde(
-                serialize_with = "ordered_map",
-                default,
-                skip_serializing_if = "HashMap::is_empty"
-            )]
-            pub localizations: HashMap<String, TranslationTypeContainer>,
-        }
+        states
     }
 
-    pub mod output {
-        use crate::types::inoutoutput::TranslationValue;
-        use enum_const_value::EnumConstValue;
+    fn plural_states(plural: &Plural) -> Vec<&str> {
+        [
+            &plural.zero,
+            &plural.one,
+            &plural.two,
+            &plural.few,
+            &plural.many,
+            &plural.other,
+        ]
+        .into_iter()
+        .flatten()
+        .flat_map(unit_states)
+        .collect()
+    }
+}
 
-        use serde::Serialize;
-        use std::collections::BTreeMap;
-        use std::error::Error;
-        use std::fmt::{Display, Formatter};
-        use std::path::PathBuf;
-        use std::string::FromUtf8Error;
+pub mod specifier {
+    use crate::prelude::*;
+    use alloc::collections::BTreeMap;
+    use regex::Captures;
 
-        #[derive(Clone, Debug)]
-        pub enum ParsedError {
-            ParseToJson(String),
-            InvalidUtf8(String),
-            Io(String),
-            InvalidTranslationKey(String),
-        }
+    /// Matches a printf style specifier as used by Apple, `%%` is matched as well so it can be skipped
+    const SPECIFIER_PATTERN: &str = r"%%|%(?P<position>\d+\$)?(?P<flags>[-+#0]*(?:\d+|\*)?(?:\.(?:\d+|\*))?)(?P<length>hh|h|ll|l|q|z|t|j)?(?P<conversion>[@diuxXofFeEgGaAcsp])";
 
-        impl Display for ParsedError {
-            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-                match self {
-                    ParsedError::ParseToJson(error) => {
-                        write!(f, "Invalid XCStrings file: {}", error)
-                    }
-                    ParsedError::InvalidUtf8(error) => write!(f, "Invalid UTF8: {}", error),
-                    ParsedError::Io(error) => write!(f, "IO error: {}", error),
-                    ParsedError::InvalidTranslationKey(key) => {
-                        write!(f, "Invalid translation key: {}", key)
-                    }
-                }
-            }
-        }
+    /// Converts the Apple specifiers to their Java/Android counterpart, e.g. `%1$@` to `%1$s` and `%lld` to `%d`.
+    /// Substitutions (`%#@count@`) are kept as is, see [resolve_substitutions].
+    pub fn to_android(value: &str) -> String {
+        cached_regex!(re, &format!("{SUBSTITUTION_PATTERN}|{SPECIFIER_PATTERN}"));
 
-        impl Error for ParsedError {
-            fn source(&self) -> Option<&(dyn Error + 'static)> {
-                None
+        re.replace_all(value, |captures: &Captures| {
+            let Some(conversion) = captures.name("conversion") else {
+                return captures[0].to_string();
+            };
+            let conversion = match conversion.as_str() {
+                "@" => "s",
+                "i" | "u" => "d",
+                other => other,
+            };
+
+            format!(
+                "%{}{}{}",
+                captures.name("position").map_or("", |m| m.as_str()),
+                &captures["flags"],
+                conversion
+            )
+        })
+        .to_string()
+    }
+
+    /// The inverse of [to_android], `%s` becomes `%@` and `%d` becomes `%lld`
+    pub fn from_android(value: &str) -> String {
+        cached_regex!(re, SPECIFIER_PATTERN);
+
+        re.replace_all(value, |captures: &Captures| {
+            let Some(conversion) = captures.name("conversion") else {
+                return captures[0].to_string();
+            };
+            let conversion = match conversion.as_str() {
+                "s" => "@",
+                "d" => "lld",
+                other => other,
+            };
+
+            format!(
+                "%{}{}{}",
+                captures.name("position").map_or("", |m| m.as_str()),
+                &captures["flags"],
+                conversion
+            )
+        })
+        .to_string()
+    }
+
+    /// Specifiers which are only understood by Apple platforms, these crash or misbehave on Android
+    pub fn ios_only_specifiers(value: &str) -> Vec<String> {
+        specifiers(value)
+            .into_iter()
+            .filter(Specifier::is_ios_only)
+            .map(|specifier| specifier.raw)
+            .collect()
+    }
+
+    /// Matches what Android can't format the Apple way: `%@`, substitutions (also after `#@` was
+    /// converted to `#s`), length modifiers and the Apple only conversions `%D`, `%U`, `%O`,
+    /// `%S` and `%C`. It's independent of [SPECIFIER_PATTERN], so it finds what [to_android]
+    /// missed. `%%` is matched as well so it can be skipped.
+    const LEFTOVER_PATTERN: &str = r"%%|%(?:\d+\$)?(?:#@[^@\s]*@|[-+# 0]*(?:\d+|\*)?(?:\.(?:\d+|\*))?(?:(?:hh|h|ll|l|q|z|t|j|L)[a-zA-Z@]|[@DUOSC]))|%(?:\d+\$)?[-+ 0]*#[-+# 0]*\d*s";
+
+    /// The Apple only specifiers left in `value` after converting it for Android, like `%@` or a
+    /// length modifier. Scanned independently of [to_android], so it finds what it missed.
+    pub fn android_leftovers(value: &str) -> Vec<String> {
+        cached_regex!(re, LEFTOVER_PATTERN);
+
+        re.find_iter(value)
+            .map(|m| m.as_str().to_string())
+            .filter(|token| token != "%%")
+            .collect()
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Specifier {
+        pub raw: String,
+        /// The explicit argument position, like the 2 in `%2$@`
+        pub position: Option<usize>,
+        /// The length modifier, like `ll` in `%lld`
+        pub length: Option<String>,
+        pub conversion: char,
+    }
+
+    /// The kind of argument a specifier consumes
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub enum ArgumentType {
+        Integer,
+        Float,
+        /// `%@` and `%s`
+        Object,
+        Char,
+        Pointer,
+    }
+
+    impl ArgumentType {
+        pub fn name(&self) -> &'static str {
+            match self {
+                ArgumentType::Integer => "integer",
+                ArgumentType::Float => "float",
+                ArgumentType::Object => "object",
+                ArgumentType::Char => "char",
+                ArgumentType::Pointer => "pointer",
             }
         }
+    }
 
-        impl From<FromUtf8Error> for ParsedError {
-            fn from(value: FromUtf8Error) -> Self {
-                ParsedError::InvalidUtf8(value.to_string())
-            }
+    /// Matches a named substitution like `%#@count@`, the name is captured
+    const SUBSTITUTION_PATTERN: &str = r"%(?:\d+\$)?#@([^@\s]+)@";
+
+    /// The names of the substitutions in `value`, like `count` in `%#@count@`
+    pub fn substitution_names(value: &str) -> Vec<String> {
+        cached_regex!(re, SUBSTITUTION_PATTERN);
+
+        re.captures_iter(value)
+            .map(|captures| captures[1].to_string())
+            .collect()
+    }
+
+    impl Specifier {
+        pub fn is_ios_only(&self) -> bool {
+            self.conversion == '@' || self.length.is_some()
         }
 
-        impl From<std::io::Error> for ParsedError {
-            fn from(value: std::io::Error) -> Self {
-                ParsedError::Io(value.to_string())
+        pub fn argument_type(&self) -> ArgumentType {
+            match self.conversion {
+                'f' | 'F' | 'e' | 'E' | 'g' | 'G' | 'a' | 'A' => ArgumentType::Float,
+                '@' | 's' => ArgumentType::Object,
+                'c' => ArgumentType::Char,
+                'p' => ArgumentType::Pointer,
+                _ => ArgumentType::Integer,
             }
         }
+    }
 
-        impl From<serde_json::Error> for ParsedError {
-            fn from(value: serde_json::Error) -> Self {
-                ParsedError::ParseToJson(value.to_string())
+    /// All specifiers in `value` in order of appearance, `%%` and substitutions (`%#@count@`)
+    /// are skipped
+    pub fn specifiers(value: &str) -> Vec<Specifier> {
+        cached_regex!(re, &format!("{SUBSTITUTION_PATTERN}|{SPECIFIER_PATTERN}"));
+
+        re.captures_iter(value)
+            .filter_map(|captures| {
+                let conversion = captures.name("conversion")?;
+
+                Some(Specifier {
+                    raw: captures[0].to_string(),
+                    position: captures
+                        .name("position")
+                        .and_then(|p| p.as_str().trim_end_matches('$').parse().ok()),
+                    length: captures.name("length").map(|l| l.as_str().to_string()),
+                    conversion: conversion.as_str().chars().next().unwrap(),
+                })
+            })
+            .collect()
+    }
+
+    /// Replaces every `%#@name@` with the `other` case of its substitution, where `%arg` becomes
+    /// the specifier (like `%1$lld`). Android has no substitutions, so the other plural cases
+    /// are lost. Without an `other` case only the specifier is used, unknown names are kept as
+    /// is (and reported by `leftover_ios_specifiers`).
+    pub fn resolve_substitutions(
+        value: &str,
+        substitutions: &BTreeMap<String, crate::types::output::Substitution>,
+    ) -> String {
+        cached_regex!(re, SUBSTITUTION_PATTERN);
+
+        re.replace_all(value, |captures: &Captures| {
+            let Some(substitution) = substitutions.get(&captures[1]) else {
+                return captures[0].to_string();
+            };
+            let specifier = substitution.specifier();
+
+            match substitution
+                .plural
+                .iter()
+                .find(|single| single.variate == crate::types::output::PluralVariate::Other)
+            {
+                Some(other) => other.translation_value.value.replace("%arg", &specifier),
+                None => specifier,
             }
-        }
+        })
+        .to_string()
+    }
 
-        pub type ParsedResult = Result<Parsed, ParsedError>;
+    /// Every format token as written, substitutions (`%#@count@`) first and then the specifiers
+    pub fn tokens(value: &str) -> Vec<String> {
+        cached_regex!(re, SUBSTITUTION_PATTERN);
 
-        #[derive(Debug, Clone)]
-        pub struct Parsed {
-            pub localizable: Localizable,
-            pub translation: super::input::Translation,
-        }
+        let mut tokens: Vec<_> = re
+            .find_iter(value)
+            .map(|m| m.as_str().to_string())
+            .collect();
 
-        #[derive(Debug, Clone)]
-        pub struct Localizable {
-            pub source_language: String,
-            pub single_translation: Vec<SingleTranslation>,
-        }
+        tokens.extend(specifiers(value).into_iter().map(|specifier| specifier.raw));
 
-        #[derive(Debug, Clone)]
-        pub struct SingleTranslation {
-            pub key_raw: String,
-            pub key_alphanumeric: String,
-            pub localization_value: LocalizationValue,
-            pub comment: String,
-        }
+        tokens
+    }
 
-        #[derive(Debug, Clone, Default)]
-        pub struct LocalizedPerLanguageInfo {
-            pub word_count: usize,
-            pub translations: Vec<SingleLocalizedPerLanguage>,
-        }
+    /// Applies `f` to the literal text between the format tokens (specifiers, `%%` and
+    /// substitutions), the tokens are kept as written
+    pub fn map_text(value: &str, mut f: impl FnMut(&str) -> String) -> String {
+        cached_regex!(re, &format!("{SUBSTITUTION_PATTERN}|{SPECIFIER_PATTERN}"));
 
-        #[derive(Debug, Clone)]
-        pub struct SingleLocalizedPerLanguage {
-            pub key_raw: String,
-            pub key_alphanumeric: String,
-            pub translation: Translation,
-            pub comment: String,
+        let mut mapped = String::new();
+        let mut last = 0;
+
+        for token in re.find_iter(value) {
+            if token.start() > last {
+                mapped.push_str(&f(&value[last..token.start()]));
+            }
+
+            mapped.push_str(token.as_str());
+            last = token.end();
         }
 
-        #[derive(Debug, Clone)]
-        pub struct LocalizedPerLanguage {
-            pub source_language: String,
-            pub language_localized: BTreeMap<String, LocalizedPerLanguageInfo>,
+        if last < value.len() {
+            mapped.push_str(&f(&value[last..]));
         }
 
-        impl Localizable {
-            pub fn localized_per_language(&self) -> LocalizedPerLanguage {
-                let mut localized_per_language = LocalizedPerLanguage {
-                    source_language: self.source_language.to_string(),
-                    language_localized: Default::default(),
-                };
+        mapped
+    }
 
-                for single_translation in &self.single_translation {
-                    for (language, translation) in
-                        &single_translation.localization_value.language_translation
-                    {
-                        let single_localized_per_language = localized_per_language
-                            .language_localized
-                            .entry(language.to_string())
-                            .or_default();
+    /// The argument position of every specifier, non-positional specifiers take the next position
+    pub fn argument_positions(value: &str) -> Vec<usize> {
+        argument_types(value)
+            .into_iter()
+            .map(|(position, _)| position)
+            .collect()
+    }
 
-                        single_localized_per_language.translations.push(
-                            SingleLocalizedPerLanguage {
-                                key_raw: single_translation.key_raw.to_string(),
-                                key_alphanumeric: single_translation.key_alphanumeric.to_string(),
-                                translation: translation.clone(),
-                                comment: single_translation.comment.to_string(),
-                            },
-                        );
+    /// A piece of a value, see [segments]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum Segment {
+        /// Literal text, `%%` is already unescaped to `%`
+        Text(String),
+        /// A specifier with its argument position
+        Argument(usize, Specifier),
+        /// The name of a substitution, like `count` in `%#@count@`
+        Substitution(String),
+    }
 
-                        let length = match translation {
-                            Translation::Localization(l) => words_count::count(&l.value).words,
-                            Translation::PluralVariation(pv) => pv
-                                .iter()
-                                .map(|single| {
-                                    words_count::count(&single.translation_value.value).words
-                                })
-                                .sum(),
-                        };
+    /// Splits `value` in literal text, specifiers and substitutions, e.g. to rewrite the
+    /// specifiers in another placeholder syntax while escaping the text. Substitutions don't
+    /// take a sequential position, they have their own `argNum`.
+    pub fn segments(value: &str) -> Vec<Segment> {
+        cached_regex!(re, &format!("{SUBSTITUTION_PATTERN}|{SPECIFIER_PATTERN}"));
 
-                        single_localized_per_language.word_count += length;
-                    }
-                }
+        let mut segments = vec![];
+        let mut text = String::new();
+        let mut last = 0;
+        let mut sequential = 0;
 
-                for (language, localized) in &localized_per_language.language_localized {
-                    log::debug!(
-                        "Language: {} word count: {}",
-                        language,
-                        localized.word_count
-                    )
+        for captures in re.captures_iter(value) {
+            let whole = captures.get(0).unwrap();
+
+            text.push_str(&value[last..whole.start()]);
+            last = whole.end();
+
+            if let Some(name) = captures.get(1) {
+                if !text.is_empty() {
+                    segments.push(Segment::Text(core::mem::take(&mut text)));
                 }
 
-                localized_per_language
+                segments.push(Segment::Substitution(name.as_str().to_string()));
+
+                continue;
             }
-        }
-        #[derive(Debug, Clone, Default)]
-        pub struct AndroidWriteConfig {
-            pub write_in: PathBuf,
-            pub only_write_language_code: Option<String>,
-        }
 
-        #[derive(Debug, Clone, Default)]
-        pub struct AndroidLocalizeConfig {
-            pub app_name: String,
-            pub write_config: Option<AndroidWriteConfig>,
-        }
+            let Some(specifier) = specifiers(whole.as_str()).pop() else {
+                // `%%`
+                text.push('%');
 
-        #[derive(Debug, Clone, Serialize)]
-        pub struct WrittenXml {
-            pub language_code: String,
-            pub sub_dir: String,
-        }
+                continue;
+            };
+            let position = match specifier.position {
+                Some(position) => position,
+                None => {
+                    sequential += 1;
 
-        #[derive(Debug, Clone, Default)]
-        pub struct LocalizedForAndroid {
-            pub sorted_languages: BTreeMap<String, String>,
-            pub written_xmls: Vec<WrittenXml>,
-        }
+                    sequential
+                }
+            };
 
-        impl LocalizedPerLanguage {
-            pub fn localized_for_android(
-                &self,
-                config: AndroidLocalizeConfig,
-            ) -> Result<LocalizedForAndroid, ParsedError> {
-                let mut localized_for_android: LocalizedForAndroid = Default::default();
+            if !text.is_empty() {
+                segments.push(Segment::Text(core::mem::take(&mut text)));
+            }
 
-                for (language, translations) in &self.language_localized {
-                    let mut xml = vec![];
-                    let ordered = translations.clone();
+            segments.push(Segment::Argument(position, specifier));
+        }
 
-                    for translation in ordered.translations {
-                        let content = match &translation.translation {
-                            Translation::Localization(localization) => {
-                                format!(
-                                    "<string name=\"{}\">{}</string>",
-                                    translation.key_alphanumeric,
-                                    localization.sanitize_for_android()
-                                )
-                            }
-                            Translation::PluralVariation(plural) => {
-                                let mut temp = vec![format!(
-                                    "<plurals name=\"{}\">",
-                                    translation.key_alphanumeric
-                                )];
+        text.push_str(&value[last..]);
 
-                                for single_plural in plural {
-                                    temp.push(format!(
-                                        "<item quantity=\"{}\">{}</item>",
-                                        single_plural.variate.android_key(),
-                                        single_plural.translation_value.sanitize_for_android()
-                                    ));
-                                }
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
 
-                                temp.push("</plurals>".to_string());
+        segments
+    }
 
-                                temp.join("\n")
-                            }
-                        };
+    /// Like [argument_positions], with the type of the argument at that position
+    pub fn argument_types(value: &str) -> Vec<(usize, ArgumentType)> {
+        let mut sequential = 0;
 
-                        xml.push(content);
-                    }
+        specifiers(value)
+            .into_iter()
+            .map(|specifier| {
+                let position = match specifier.position {
+                    Some(position) => position,
+                    None => {
+                        sequential += 1;
 
-                    if !config.app_name.is_empty() {
-                        xml.insert(
-                            0,
-                            format!("<string name=\"app_name\">{}</string>", config.app_name),
-                        );
+                        sequential
                     }
+                };
 
-                    localized_for_android.sorted_languages.insert(
-                        language.to_string(),
-                        format!("<resources>\n{}\n</resources>", xml.join("\n")),
-                    );
-                }
+                (position, specifier.argument_type())
+            })
+            .collect()
+    }
+}
 
-                if let Some(write_config) = config.write_config {
-                    let mut written_xmls = vec![];
+pub mod export {
+    use crate::prelude::*;
+    use crate::types::output::{
+        Localizable, Parsed, PluralVariate, SinglePluralVariation, Substitution, Translation,
+    };
+    use alloc::collections::{BTreeMap, BTreeSet};
 
-                    for (language, content) in &localized_for_android.sorted_languages {
-                        if let Some(lan) = &write_config.only_write_language_code {
-                            if lan != language {
-                                continue;
-                            }
-                        }
+    /// Quotes a CSV field when it contains a separator, quote or newline
+    fn csv_field(value: &str) -> String {
+        if value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
 
-                        let suffix_dir = if language == &self.source_language {
-                            "".to_string()
-                        } else {
-                            format!("-{language}")
-                        };
-                        let sub_dir_name = format!("values{suffix_dir}");
-                        let sub_dir = write_config.write_in.join(&sub_dir_name);
+    /// A gettext string literal, including the quotes
+    fn po_string(value: &str) -> String {
+        format!(
+            "\"{}\"",
+            value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n")
+                .replace('\t', "\\t")
+        )
+    }
 
-                        if !sub_dir.exists() {
-                            std::fs::create_dir(&sub_dir)?;
-                        }
+    /// Also used for XML, the escaped characters are the same
+    fn html_escape(value: &str) -> String {
+        value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&#39;")
+    }
 
-                        let path_to_file = sub_dir.join("strings.xml");
+    /// A table cell with the escaped value, plural cases and devices on separate lines
+    fn html_cell(translation: Option<&Translation>) -> String {
+        match translation {
+            None => "<td class=\"missing\"></td>".to_string(),
+            Some(Translation::Localization(l)) => format!("<td>{}</td>", html_escape(&l.value)),
+            Some(Translation::PluralVariation(pv)) => format!(
+                "<td>{}</td>",
+                pv.iter()
+                    .map(|single| format!(
+                        "{}: {}",
+                        single.variate.android_key(),
+                        html_escape(&single.translation_value.value)
+                    ))
+                    .collect::<Vec<_>>()
+                    .join("<br>")
+            ),
+            Some(Translation::DeviceVariation(dv)) => format!(
+                "<td>{}</td>",
+                dv.iter()
+                    .map(|single| format!(
+                        "{}: {}",
+                        single.variate.apple_key(),
+                        html_escape(&single.translation_value.value)
+                    ))
+                    .collect::<Vec<_>>()
+                    .join("<br>")
+            ),
+        }
+    }
 
-                        std::fs::write(&path_to_file, content)?;
+    impl Parsed {
+        /// A catalog with only the keys which aren't translated yet for `language`. Only the
+        /// source and `language` localizations are kept, the source gives translators context.
+        pub fn export_untranslated(&self, language: &str) -> Parsed {
+            let mut exported = self.clone();
 
-                        written_xmls.push(WrittenXml {
-                            language_code: language.to_string(),
-                            sub_dir: sub_dir_name,
-                        })
-                    }
+            exported.localizable.single_translation.retain(|st| {
+                !st.translation_for(language)
+                    .is_some_and(Translation::is_translated)
+            });
 
-                    localized_for_android.written_xmls = written_xmls;
-                }
+            for single_translation in &mut exported.localizable.single_translation {
+                single_translation
+                    .localization_value
+                    .language_translation
+                    .retain(|l, _| l == language || l == &self.localizable.source_language);
+            }
 
-                Ok(localized_for_android)
+            exported.translation.strings.retain(|key, _| {
+                exported
+                    .localizable
+                    .single_translation
+                    .iter()
+                    .any(|st| &st.key_raw == key)
+            });
+
+            for input in exported.translation.strings.values_mut() {
+                input
+                    .localizations
+                    .retain(|l, _| l == language || l == &self.localizable.source_language);
             }
-        }
 
-        #[derive(Debug, Clone, Default)]
-        pub struct LocalizationValue {
-            pub language_translation: BTreeMap<String, Translation>,
+            exported
         }
+    }
 
-        #[derive(Debug, Clone)]
-        pub struct SinglePluralVariation {
-            pub variate: PluralVariate,
-            pub translation_value: TranslationValue,
+    /// Escapes a key or value for a `"key" = "value";` line in a `.strings` file
+    pub fn escape_strings_value(value: &str) -> String {
+        value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+            .replace('\r', "\\r")
+            .replace('\t', "\\t")
+    }
+
+    /// Comments can't contain `*/`, it would end the comment early
+    fn escape_strings_comment(comment: &str) -> String {
+        comment.replace("*/", "* /")
+    }
+
+    impl Localizable {
+        /// The `Localizable.strings` content for `language`, comments are written above the keys.
+        /// Plurals can't be expressed in `.strings` files (they belong in `.stringsdict`) and
+        /// are skipped, as are keys without a translation for `language`.
+        pub fn to_strings_file(&self, language: &str) -> String {
+            self.strings_file(language, |_| true)
         }
 
-        #[derive(Debug, Clone)]
-        pub enum Translation {
-            Localization(TranslationValue),
-            PluralVariation(Vec<SinglePluralVariation>),
+        /// Per language an `InfoPlist.strings` file with only the keys in `allowlist`, e.g.
+        /// `CFBundleDisplayName` and `NSCameraUsageDescription`, in the format of
+        /// [Localizable::to_strings_file]. Languages without an allowlisted key are left out.
+        pub fn to_info_plist_strings(&self, allowlist: &[&str]) -> BTreeMap<String, String> {
+            self.languages()
+                .into_iter()
+                .filter_map(|language| {
+                    let content = self.strings_file(&language, |key| allowlist.contains(&key));
+
+                    (!content.is_empty()).then_some((language, content))
+                })
+                .collect()
         }
 
-        impl Translation {
-            pub fn expect_localization(self) -> TranslationValue {
-                match self {
-                    Translation::Localization(tv) => tv,
-                    _ => panic!(),
+        fn strings_file(&self, language: &str, include: impl Fn(&str) -> bool) -> String {
+            let mut entries = vec![];
+
+            for single_translation in &self.single_translation {
+                if !include(&single_translation.key_raw) {
+                    continue;
                 }
-            }
 
-            pub fn expect_plural_variation(self) -> Vec<SinglePluralVariation> {
-                match self {
-                    Translation::PluralVariation(pv) => pv,
-                    _ => panic!(),
+                let Some(l) = single_translation
+                    .translation_for(language)
+                    .and_then(Translation::simple_value)
+                else {
+                    continue;
+                };
+                let mut entry = String::new();
+
+                if !single_translation.comment.is_empty() {
+                    entry.push_str(&format!(
+                        "/* {} */\n",
+                        escape_strings_comment(&single_translation.comment)
+                    ));
                 }
+
+                entry.push_str(&format!(
+                    "\"{}\" = \"{}\";",
+                    escape_strings_value(&single_translation.key_raw),
+                    escape_strings_value(&l.value)
+                ));
+                entries.push(entry);
             }
-        }
 
-        #[derive(Debug, Clone, Eq, Hash, PartialEq, EnumConstValue)]
-        pub enum PluralVariate {
-            Zero,
-            One,
-            Two,
-            Few,
-            Many,
-            Other,
+            entries.join("\n\n")
         }
 
-        impl PluralVariate {
-            pub fn from_android_key(str: &str) -> Option<Self> {
-                PluralVariate::all_values()
-                    .into_iter()
-                    .find(|variate| variate.android_key() == str)
-            }
+        /// A gettext template with a `msgid` per key and empty `msgstr`s, the comments are
+        /// added as `#.` lines. Keys which are a plural in the source get the key as `msgctxt`,
+        /// the `one` case as `msgid` and the `other` case as `msgid_plural`.
+        pub fn to_pot(&self) -> String {
+            let mut entries = vec![[
+                "msgid \"\"",
+                "msgstr \"\"",
+                "\"Content-Type: text/plain; charset=UTF-8\\n\"",
+            ]
+            .join("\n")];
 
-            pub fn android_key(&self) -> &'static str {
-                match self {
-                    PluralVariate::Zero => "Zero",
-                    PluralVariate::One => "one",
-                    PluralVariate::Two => "two",
-                    PluralVariate::Few => "few",
-                    PluralVariate::Many => "many",
-                    PluralVariate::Other => "other",
+            for single_translation in &self.single_translation {
+                let mut entry = vec![];
+
+                for line in single_translation.comment.lines() {
+                    entry.push(format!("#. {line}"));
                 }
-            }
-        }
 
-        impl TranslationValue {
-            pub fn sanitize_for_android(&self) -> String {
-                self.value.replace('\'', "\\'").replace("$lld", "$d")
+                let key = po_string(&single_translation.key_raw);
+
+                match single_translation.translation_for(&self.source_language) {
+                    Some(Translation::PluralVariation(pv)) => {
+                        // Missing cases fall back to the key, like a missing source value
+                        let case = |variate: PluralVariate| {
+                            pv.iter()
+                                .find(|single| single.variate == variate)
+                                .map_or(key.to_string(), |single| {
+                                    po_string(&single.translation_value.value)
+                                })
+                        };
+
+                        entry.push(format!("msgctxt {key}"));
+                        entry.push(format!("msgid {}", case(PluralVariate::One)));
+                        entry.push(format!("msgid_plural {}", case(PluralVariate::Other)));
+                        entry.push("msgstr[0] \"\"".to_string());
+                        entry.push("msgstr[1] \"\"".to_string());
+                    }
+                    _ => {
+                        entry.push(format!("msgid {key}"));
+                        entry.push("msgstr \"\"".to_string());
+                    }
+                }
+
+                entries.push(entry.join("\n"));
             }
+
+            entries.join("\n\n") + "\n"
         }
-    }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::types::output::{AndroidLocalizeConfig, AndroidWriteConfig};
+        /// A TMX translation memory with a `<tu>` per key, plurals get a `<tu>` per case with
+        /// the case appended to the id like `key[one]`
+        pub fn to_tmx(&self) -> String {
+            let mut tmx = vec![
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>".to_string(),
+                "<tmx version=\"1.4\">".to_string(),
+                format!(
+                    "<header creationtool=\"{}\" creationtoolversion=\"{}\" segtype=\"sentence\" o-tmf=\"xcstrings\" adminlang=\"{language}\" srclang=\"{language}\" datatype=\"plaintext\"/>",
+                    env!("CARGO_PKG_NAME"),
+                    env!("CARGO_PKG_VERSION"),
+                    language = html_escape(&self.source_language)
+                ),
+                "<body>".to_string(),
+            ];
+            // Source first, then the other languages in order
+            let languages: Vec<_> = [self.source_language.to_string()]
+                .into_iter()
+                .chain(
+                    self.languages()
+                        .into_iter()
+                        .filter(|language| language != &self.source_language),
+                )
+                .collect();
+            let mut unit = |tuid: String, segments: Vec<(&String, &str)>| {
+                tmx.push(format!("<tu tuid=\"{}\">", html_escape(&tuid)));
+
+                for (language, value) in segments {
+                    tmx.push(format!(
+                        "<tuv xml:lang=\"{}\"><seg>{}</seg></tuv>",
+                        html_escape(language),
+                        html_escape(value)
+                    ));
+                }
+
+                tmx.push("</tu>".to_string());
+            };
+
+            for single_translation in &self.single_translation {
+                match single_translation.translation_for(&self.source_language) {
+                    Some(Translation::PluralVariation(source)) => {
+                        for case in source {
+                            let segments = languages
+                                .iter()
+                                .filter_map(|language| {
+                                    let Some(Translation::PluralVariation(pv)) =
+                                        single_translation.translation_for(language)
+                                    else {
+                                        return None;
+                                    };
+
+                                    pv.iter().find(|single| single.variate == case.variate).map(
+                                        |single| {
+                                            (language, single.translation_value.value.as_str())
+                                        },
+                                    )
+                                })
+                                .collect();
+
+                            unit(
+                                format!(
+                                    "{}[{}]",
+                                    single_translation.key_raw,
+                                    case.variate.android_key()
+                                ),
+                                segments,
+                            );
+                        }
+                    }
+                    Some(Translation::Localization(_) | Translation::DeviceVariation(_)) => {
+                        let segments = languages
+                            .iter()
+                            .filter_map(|language| {
+                                single_translation
+                                    .translation_for(language)
+                                    .and_then(Translation::simple_value)
+                                    .map(|tv| (language, tv.value.as_str()))
+                            })
+                            .collect();
+
+                        unit(single_translation.key_raw.to_string(), segments);
+                    }
+                    None => {}
+                }
+            }
+
+            tmx.extend(["</body>", "</tmx>"].map(String::from));
+
+            tmx.join("\n")
+        }
+
+        /// A standalone HTML page with a row per key: the key, comment, source and every other
+        /// language. Missing translations are highlighted.
+        pub fn to_html_review(&self) -> String {
+            let languages: Vec<_> = self
+                .languages()
+                .into_iter()
+                .filter(|language| language != &self.source_language)
+                .collect();
+            let mut html = vec![
+                "<!DOCTYPE html>".to_string(),
+                "<html>".to_string(),
+                "<head>".to_string(),
+                "<meta charset=\"utf-8\">".to_string(),
+                "<style>table { border-collapse: collapse; } td, th { border: 1px solid #ccc; padding: 4px; } td.missing { background: #fdd; }</style>".to_string(),
+                "</head>".to_string(),
+                "<body>".to_string(),
+                "<table>".to_string(),
+            ];
+            let mut header = format!(
+                "<tr><th>key</th><th>comment</th><th>{}</th>",
+                html_escape(&self.source_language)
+            );
+
+            for language in &languages {
+                header.push_str(&format!("<th>{}</th>", html_escape(language)));
+            }
+
+            header.push_str("</tr>");
+            html.push(header);
+
+            for single_translation in &self.single_translation {
+                let mut row = format!(
+                    "<tr><td>{}</td><td>{}</td>{}",
+                    html_escape(&single_translation.key_raw),
+                    html_escape(&single_translation.comment),
+                    html_cell(single_translation.translation_for(&self.source_language))
+                );
+
+                for language in &languages {
+                    row.push_str(&html_cell(single_translation.translation_for(language)));
+                }
+
+                row.push_str("</tr>");
+                html.push(row);
+            }
+
+            html.extend(["</table>", "</body>", "</html>"].map(String::from));
+
+            html.join("\n")
+        }
+
+        /// A CSV with the key, comment and source value, the column for `language` is left empty.
+        /// Plurals get a row per variate, the key is suffixed with the variate like `key[one]`.
+        pub fn export_template_csv(&self, language: &str) -> String {
+            let mut lines = vec![format!(
+                "key,comment,{},{}",
+                csv_field(&self.source_language),
+                csv_field(language)
+            )];
+
+            for single_translation in &self.single_translation {
+                let Some(source) = single_translation.translation_for(&self.source_language) else {
+                    continue;
+                };
+                let comment = csv_field(&single_translation.comment);
+
+                match source {
+                    Translation::Localization(l) => lines.push(format!(
+                        "{},{},{},",
+                        csv_field(&single_translation.key_raw),
+                        comment,
+                        csv_field(&l.value)
+                    )),
+                    Translation::PluralVariation(pv) => {
+                        for single in pv {
+                            lines.push(format!(
+                                "{},{},{},",
+                                csv_field(&format!(
+                                    "{}[{}]",
+                                    single_translation.key_raw,
+                                    single.variate.android_key()
+                                )),
+                                comment,
+                                csv_field(&single.translation_value.value)
+                            ))
+                        }
+                    }
+                    Translation::DeviceVariation(dv) => {
+                        for single in dv {
+                            lines.push(format!(
+                                "{},{},{},",
+                                csv_field(&format!(
+                                    "{}[{}]",
+                                    single_translation.key_raw,
+                                    single.variate.apple_key()
+                                )),
+                                comment,
+                                csv_field(&single.translation_value.value)
+                            ))
+                        }
+                    }
+                }
+            }
+
+            lines.join("\n")
+        }
+    }
+
+    impl Localizable {
+        /// Sorts the cases of every plural by [crate::types::output::PluralVariate::order]
+        pub fn canonicalize_plurals(&mut self) {
+            for single_translation in &mut self.single_translation {
+                for translation in single_translation
+                    .localization_value
+                    .language_translation
+                    .values_mut()
+                {
+                    if let Translation::PluralVariation(pv) = translation {
+                        pv.sort_by(|a, b| a.variate.cmp(&b.variate));
+                    }
+                }
+            }
+        }
+
+        /// Serializes back to the xcstrings JSON. Keys and languages are sorted and plurals are
+        /// canonicalized first, so the output doesn't depend on the order of the vectors.
+        pub fn to_xcstrings(&self) -> String {
+            self.to_xcstrings_with_state_names(&Default::default())
+        }
+
+        /// Like [Localizable::to_xcstrings], but the states are written with the custom names,
+        /// the counterpart of [crate::types::output::ParseConfig::state_names]
+        pub fn to_xcstrings_with_state_names(
+            &self,
+            state_names: &crate::types::output::StateNames,
+        ) -> String {
+            let mut canonical = self.clone();
+
+            canonical.canonicalize_plurals();
+
+            for single_translation in &mut canonical.single_translation {
+                let values = single_translation
+                    .localization_value
+                    .language_translation
+                    .values_mut()
+                    .flat_map(Translation::translation_values_mut)
+                    .chain(
+                        single_translation
+                            .substitutions
+                            .values_mut()
+                            .flat_map(|substitutions| substitutions.values_mut())
+                            .flat_map(|substitution| substitution.plural.iter_mut())
+                            .map(|single| &mut single.translation_value),
+                    );
+
+                for tv in values {
+                    tv.state = state_names.denormalize(&tv.state);
+                }
+            }
+
+            let translation = crate::types::input::Translation {
+                source_language: canonical.source_language.to_string(),
+                strings: canonical
+                    .single_translation
+                    .iter()
+                    .map(|st| {
+                        (
+                            st.key_raw.to_string(),
+                            crate::types::input::Language {
+                                comment: st.comment.to_string(),
+                                should_translate: (!st.should_translate).then_some(false),
+                                localizations: st
+                                    .localization_value
+                                    .language_translation
+                                    .iter()
+                                    .map(|(language, translation)| {
+                                        let mut container =
+                                            crate::android::input_container(translation);
+
+                                        if let (
+                                            crate::types::input::TranslationTypeContainer::StringUnit(
+                                                su,
+                                            ),
+                                            Some(substitutions),
+                                        ) = (&mut container, st.substitutions.get(language))
+                                        {
+                                            su.substitutions = substitutions
+                                                .iter()
+                                                .map(|(name, substitution)| {
+                                                    (name.to_string(), crate::android::input_substitution(substitution))
+                                                })
+                                                .collect();
+                                        }
+
+                                        (language.to_string(), container)
+                                    })
+                                    .collect(),
+                            },
+                        )
+                    })
+                    .collect(),
+                version: self.version.to_string(),
+            };
+
+            serde_json::to_string_pretty(&translation).unwrap()
+        }
+    }
+
+    /// Escapes the characters ICU MessageFormat treats specially: a `'` is doubled, `{`, `}`
+    /// and (inside a plural, where it means the count) `#` are quoted
+    pub fn escape_icu(text: &str, in_plural: bool) -> String {
+        let mut escaped = String::new();
+
+        for c in text.chars() {
+            match c {
+                '\'' => escaped.push_str("''"),
+                '{' | '}' => escaped.push_str(&format!("'{c}'")),
+                '#' if in_plural => escaped.push_str("'#'"),
+                c => escaped.push(c),
+            }
+        }
+
+        escaped
+    }
+
+    /// An ICU message with the specifiers as `{argN}` placeholders. A substitution becomes an
+    /// ICU plural on its `argNum`, or a `{name}` argument when it's missing from `substitutions`.
+    fn icu_message(
+        value: &str,
+        escape: bool,
+        in_plural: bool,
+        substitutions: Option<&BTreeMap<String, Substitution>>,
+    ) -> String {
+        crate::specifier::segments(value)
+            .into_iter()
+            .map(|segment| match segment {
+                crate::specifier::Segment::Text(text) if escape => escape_icu(&text, in_plural),
+                crate::specifier::Segment::Text(text) => text,
+                crate::specifier::Segment::Argument(position, _) => format!("{{arg{position}}}"),
+                crate::specifier::Segment::Substitution(name) => {
+                    match substitutions.and_then(|substitutions| substitutions.get(&name)) {
+                        Some(substitution) => icu_plural(
+                            substitution.arg_num,
+                            &substitution.plural,
+                            &substitution.specifier(),
+                            escape,
+                        ),
+                        None => format!("{{{name}}}"),
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// An ICU plural on `argN`, `%arg` in the cases (of a substitution) becomes `specifier`
+    fn icu_plural(
+        position: usize,
+        plural: &[SinglePluralVariation],
+        specifier: &str,
+        escape: bool,
+    ) -> String {
+        let cases: Vec<_> = plural
+            .iter()
+            .map(|single| {
+                format!(
+                    "{}{{{}}}",
+                    single.variate.android_key(),
+                    icu_message(
+                        &single.translation_value.value.replace("%arg", specifier),
+                        escape,
+                        true,
+                        None
+                    )
+                )
+            })
+            .collect();
+
+        format!("{{arg{position}, plural, {}}}", cases.join(" "))
+    }
+
+    /// The ICU arguments of a translation: the positions of the specifiers and substitutions
+    /// (`arg1` for a plural) and the names of the unknown substitutions
+    fn icu_arguments(
+        translation: &Translation,
+        substitutions: Option<&BTreeMap<String, Substitution>>,
+    ) -> (BTreeSet<usize>, BTreeSet<String>) {
+        let mut positions = BTreeSet::new();
+        let mut names = BTreeSet::new();
+
+        if let Translation::PluralVariation(_) = translation {
+            positions.insert(1);
+        }
+
+        for tv in translation.translation_values() {
+            for segment in crate::specifier::segments(&tv.value) {
+                match segment {
+                    crate::specifier::Segment::Text(_) => {}
+                    crate::specifier::Segment::Argument(position, _) => {
+                        positions.insert(position);
+                    }
+                    crate::specifier::Segment::Substitution(name) => {
+                        match substitutions.and_then(|substitutions| substitutions.get(&name)) {
+                            Some(substitution) => {
+                                positions.insert(substitution.arg_num);
+                            }
+                            None => {
+                                names.insert(name);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        (positions, names)
+    }
+
+    /// The ICU message of a translation, a plural becomes an ICU plural on `arg1`
+    fn icu_translation(
+        translation: &Translation,
+        escape_icu: bool,
+        substitutions: Option<&BTreeMap<String, Substitution>>,
+    ) -> String {
+        match translation {
+            Translation::Localization(l) => icu_message(&l.value, escape_icu, false, substitutions),
+            Translation::PluralVariation(pv) => icu_plural(1, pv, "%arg", escape_icu),
+            Translation::DeviceVariation(_) => icu_message(
+                &translation
+                    .device_fallback()
+                    .cloned()
+                    .unwrap_or_default()
+                    .value,
+                escape_icu,
+                false,
+                substitutions,
+            ),
+        }
+    }
+
+    impl Localizable {
+        /// Every distinct format token across all values and languages, see
+        /// [crate::specifier::tokens]
+        pub fn distinct_placeholders(&self) -> BTreeSet<String> {
+            self.single_translation
+                .iter()
+                .flat_map(|st| st.localization_value.language_translation.values())
+                .flat_map(Translation::translation_values)
+                .flat_map(|tv| crate::specifier::tokens(&tv.value))
+                .collect()
+        }
+
+        /// Keys whose source value has a format token, for plurals in any case. Plurals without
+        /// a token are left out.
+        pub fn keys_with_placeholders(&self) -> Vec<String> {
+            self.single_translation
+                .iter()
+                .filter(|st| {
+                    st.translation_for(&self.source_language).is_some_and(|t| {
+                        t.translation_values()
+                            .iter()
+                            .any(|tv| !crate::specifier::tokens(&tv.value).is_empty())
+                    })
+                })
+                .map(|st| st.key_raw.to_string())
+                .collect()
+        }
+
+        /// Per raw key the arguments of the source value as JSON: the placeholders ordered by
+        /// position with their type and specifier, and the named substitutions (`%#@name@`).
+        /// For plurals the cases are combined.
+        pub fn placeholder_spec(&self) -> String {
+            let mut spec = serde_json::Map::new();
+
+            for single_translation in &self.single_translation {
+                let Some(source) = single_translation.translation_for(&self.source_language) else {
+                    continue;
+                };
+                let mut placeholders = BTreeMap::new();
+                let mut substitutions = vec![];
+
+                for tv in source.translation_values() {
+                    let mut sequential = 0;
+
+                    for specifier in crate::specifier::specifiers(&tv.value) {
+                        let position = specifier.position.unwrap_or_else(|| {
+                            sequential += 1;
+
+                            sequential
+                        });
+
+                        placeholders.entry(position).or_insert_with(|| {
+                            serde_json::json!({
+                                "position": position,
+                                "type": specifier.argument_type().name(),
+                                "specifier": specifier.raw,
+                            })
+                        });
+                    }
+
+                    for name in crate::specifier::substitution_names(&tv.value) {
+                        if !substitutions.contains(&name) {
+                            substitutions.push(name);
+                        }
+                    }
+                }
+
+                spec.insert(
+                    single_translation.key_raw.to_string(),
+                    serde_json::json!({
+                        "placeholders": placeholders.into_values().collect::<Vec<_>>(),
+                        "substitutions": substitutions,
+                    }),
+                );
+            }
+
+            serde_json::to_string_pretty(&spec).unwrap()
+        }
+
+        /// A single JSON with every language, keyed by language and then by raw key. The values
+        /// are ICU messages like in [Localizable::to_arb].
+        pub fn to_icu_bundle(&self, escape_icu: bool) -> String {
+            let mut bundle: BTreeMap<String, BTreeMap<String, String>> = BTreeMap::new();
+
+            for single_translation in &self.single_translation {
+                for (language, translation) in
+                    &single_translation.localization_value.language_translation
+                {
+                    bundle.entry(language.to_string()).or_default().insert(
+                        single_translation.key_raw.to_string(),
+                        icu_translation(
+                            translation,
+                            escape_icu,
+                            single_translation.substitutions.get(language),
+                        ),
+                    );
+                }
+            }
+
+            serde_json::to_string_pretty(&bundle).unwrap()
+        }
+
+        /// A Flutter ARB file for `language`, keyed by the Android resource name. Specifiers
+        /// become `{argN}` placeholders, plurals become an ICU plural on `arg1`. With
+        /// `escape_icu` the literal text is escaped (see [escape_icu]), turn it off when the
+        /// values already are ICU messages.
+        pub fn to_arb(&self, language: &str, escape_icu: bool) -> String {
+            let mut arb = serde_json::Map::new();
+
+            arb.insert("@@locale".to_string(), language.into());
+
+            for single_translation in &self.single_translation {
+                let Some(translation) = single_translation.translation_for(language) else {
+                    continue;
+                };
+                let substitutions = single_translation.substitutions.get(language);
+                let message = icu_translation(translation, escape_icu, substitutions);
+                let (positions, names) = icu_arguments(translation, substitutions);
+                let mut metadata = serde_json::Map::new();
+
+                if !single_translation.comment.is_empty() {
+                    metadata.insert(
+                        "description".to_string(),
+                        single_translation.comment.as_str().into(),
+                    );
+                }
+
+                let placeholders: serde_json::Map<_, _> = positions
+                    .into_iter()
+                    .map(|position| format!("arg{position}"))
+                    .chain(names)
+                    .map(|name| (name, serde_json::json!({})))
+                    .collect();
+
+                if !placeholders.is_empty() {
+                    metadata.insert("placeholders".to_string(), placeholders.into());
+                }
+
+                arb.insert(
+                    single_translation.key_alphanumeric.to_string(),
+                    message.into(),
+                );
+
+                if !metadata.is_empty() {
+                    arb.insert(
+                        format!("@{}", single_translation.key_alphanumeric),
+                        metadata.into(),
+                    );
+                }
+            }
+
+            serde_json::to_string_pretty(&arb).unwrap()
+        }
+    }
+
+    /// A value in [Localizable::to_yaml], variations become a map of variate → value
+    #[cfg(feature = "yaml")]
+    #[derive(serde::Serialize)]
+    #[serde(untagged)]
+    enum YamlValue<'a> {
+        Value(&'a str),
+        Variations(BTreeMap<&'static str, &'a str>),
+    }
+
+    #[cfg(feature = "yaml")]
+    impl Localizable {
+        /// A lossy key → language → value export, plurals and device variations become a map
+        /// of variate → value.
+        /// States and comments are dropped.
+        pub fn to_yaml(&self) -> String {
+            let mut yaml: BTreeMap<String, BTreeMap<String, YamlValue>> = BTreeMap::new();
+
+            for single_translation in &self.single_translation {
+                let languages = yaml
+                    .entry(single_translation.key_raw.to_string())
+                    .or_default();
+
+                for (language, translation) in
+                    &single_translation.localization_value.language_translation
+                {
+                    let value = match translation {
+                        Translation::Localization(l) => YamlValue::Value(&l.value),
+                        Translation::PluralVariation(pv) => YamlValue::Variations(
+                            pv.iter()
+                                .map(|single| {
+                                    (
+                                        single.variate.android_key(),
+                                        single.translation_value.value.as_str(),
+                                    )
+                                })
+                                .collect(),
+                        ),
+                        Translation::DeviceVariation(dv) => YamlValue::Variations(
+                            dv.iter()
+                                .map(|single| {
+                                    (
+                                        single.variate.apple_key(),
+                                        single.translation_value.value.as_str(),
+                                    )
+                                })
+                                .collect(),
+                        ),
+                    };
+
+                    languages.insert(language.to_string(), value);
+                }
+            }
+
+            serde_yaml::to_string(&yaml).unwrap()
+        }
+    }
+}
+
+pub mod android {
+    use crate::prelude::*;
+    use crate::types::inoutoutput::{DeviceValue, StringUnitContainer, TranslationValue};
+    use crate::types::input::{self, TranslationTypeContainer, VariationContainer};
+    use crate::types::output::{
+        DeviceVariate, Localizable, Parsed, ParsedError, PluralVariate, SinglePluralVariation,
+        Substitution, Translation,
+    };
+    use alloc::collections::BTreeMap;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum AndroidResource {
+        String {
+            name: String,
+            value: String,
+        },
+        Plurals {
+            name: String,
+            items: Vec<(PluralVariate, String)>,
+        },
+    }
+
+    /// Undoes the escaping done for Android, including the specifier conversion
+    pub fn unescape(value: &str) -> String {
+        let unescaped = value
+            .replace("\\u0020", " ")
+            .replace("\\'", "'")
+            .replace("\\\"", "\"")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&");
+
+        crate::specifier::from_android(&unescaped)
+    }
+
+    /// Reads the `<string>` and `<plurals>` elements of a `strings.xml` file
+    pub fn parse_resources(xml: &str) -> Result<Vec<AndroidResource>, ParsedError> {
+        cached_regex!(
+            element,
+            r#"(?s)<string\s+name="(?P<string>[^"]+)"[^>]*>(?P<value>.*?)</string>|<plurals\s+name="(?P<plurals>[^"]+)"[^>]*>(?P<items>.*?)</plurals>"#
+        );
+        cached_regex!(
+            item,
+            r#"(?s)<item\s+quantity="(?P<quantity>[^"]+)"[^>]*>(?P<value>.*?)</item>"#
+        );
+
+        if !xml.contains("<resources") {
+            return Err(ParsedError::InvalidAndroidXml(
+                "missing <resources> element".to_string(),
+            ));
+        }
+
+        let mut resources = vec![];
+
+        for captures in element.captures_iter(xml) {
+            if let Some(name) = captures.name("string") {
+                resources.push(AndroidResource::String {
+                    name: name.as_str().to_string(),
+                    value: unescape(&captures["value"]),
+                });
+            } else {
+                let mut items = vec![];
+
+                for item_captures in item.captures_iter(&captures["items"]) {
+                    let quantity = &item_captures["quantity"];
+                    let variate = PluralVariate::from_android_key(quantity).ok_or_else(|| {
+                        ParsedError::InvalidAndroidXml(format!("unknown quantity {quantity}"))
+                    })?;
+
+                    items.push((variate, unescape(&item_captures["value"])));
+                }
+
+                resources.push(AndroidResource::Plurals {
+                    name: captures["plurals"].to_string(),
+                    items,
+                });
+            }
+        }
+
+        Ok(resources)
+    }
+
+    /// The language of [Localizable::reverse_android_keys_source]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum SourceLanguage {
+        Rust,
+        Kotlin,
+    }
+
+    /// Replaces any non-alphanumeric value with a _, making it a valid Android resource name
+    pub fn resource_name(raw: &str) -> String {
+        if is_resource_name(raw) {
+            return raw.to_string();
+        }
+
+        cached_regex!(re, r"[^a-zA-Z0-9]+");
+
+        re.replace_all(raw.trim(), "_")
+            .trim_matches('_')
+            .to_lowercase()
+    }
+
+    /// True when [resource_name] wouldn't change `raw`
+    pub fn is_resource_name(raw: &str) -> bool {
+        !raw.is_empty()
+            && !raw.starts_with('_')
+            && !raw.ends_with('_')
+            && !raw.contains("__")
+            && raw
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    }
+
+    impl Localizable {
+        /// Raw keys which appear under a different name in Android than in code
+        pub fn transformed_keys(&self) -> Vec<String> {
+            self.single_translation
+                .iter()
+                .filter(|st| st.is_key_transformed())
+                .map(|st| st.key_raw.to_string())
+                .collect()
+        }
+
+        /// Maps the Android resource names back to the raw keys
+        pub fn reverse_android_keys(&self) -> BTreeMap<String, String> {
+            self.single_translation
+                .iter()
+                .map(|st| (st.key_alphanumeric.to_string(), st.key_raw.to_string()))
+                .collect()
+        }
+
+        /// The [Localizable::reverse_android_keys] as source code, `name` is the name of the
+        /// constant (Rust) or object (Kotlin)
+        pub fn reverse_android_keys_source(&self, language: SourceLanguage, name: &str) -> String {
+            let reverse = self.reverse_android_keys();
+
+            match language {
+                SourceLanguage::Rust => {
+                    let mut lines = vec![format!("pub const {name}: &[(&str, &str)] = &[")];
+
+                    for (android, raw) in reverse {
+                        lines.push(format!("    ({android:?}, {raw:?}),"));
+                    }
+
+                    lines.push("];".to_string());
+                    lines.push(String::new());
+                    lines.join("\n")
+                }
+                SourceLanguage::Kotlin => {
+                    // JSON string escapes are valid in Kotlin, except `$` starts a template
+                    let literal =
+                        |value: &str| serde_json::to_string(value).unwrap().replace('$', "\\$");
+                    let mut lines = vec![
+                        format!("object {name} {{"),
+                        "    val androidToRaw: Map<String, String> = mapOf(".to_string(),
+                    ];
+
+                    for (android, raw) in reverse {
+                        lines.push(format!(
+                            "        {} to {},",
+                            literal(&android),
+                            literal(&raw)
+                        ));
+                    }
+
+                    lines.push("    )".to_string());
+                    lines.push("}".to_string());
+                    lines.push(String::new());
+                    lines.join("\n")
+                }
+            }
+        }
+    }
+
+    impl Parsed {
+        /// Applies the values of an edited Android `strings.xml` to `language`. Matched
+        /// translations get the `needs_review` state, in both the [Localizable] and the input
+        /// [crate::types::input::Translation]. Returns the Android names that didn't match a key.
+        pub fn apply_android_edits(
+            &mut self,
+            xml: &str,
+            language: &str,
+        ) -> Result<Vec<String>, ParsedError> {
+            let reverse = self.localizable.reverse_android_keys();
+            let unit = |value: String| TranslationValue {
+                state: crate::NEEDS_REVIEW_STATE.to_string(),
+                value,
+            };
+            let mut not_found = vec![];
+
+            for resource in parse_resources(xml)? {
+                let (name, translation) = match resource {
+                    AndroidResource::String { name, value } => {
+                        (name, Translation::Localization(unit(value)))
+                    }
+                    AndroidResource::Plurals { name, items } => (
+                        name,
+                        Translation::PluralVariation(
+                            items
+                                .into_iter()
+                                .map(|(variate, value)| SinglePluralVariation {
+                                    variate,
+                                    translation_value: unit(value),
+                                })
+                                .collect(),
+                        ),
+                    ),
+                };
+
+                let Some(key_raw) = reverse.get(&name) else {
+                    log::warn!("Android key {} has no matching translation key", name);
+
+                    not_found.push(name);
+
+                    continue;
+                };
+
+                if let Some(language_input) = self.translation.strings.get_mut(key_raw) {
+                    language_input
+                        .localizations
+                        .insert(language.to_string(), input_container(&translation));
+                }
+
+                if let Some(single_translation) = self
+                    .localizable
+                    .single_translation
+                    .iter_mut()
+                    .find(|st| &st.key_raw == key_raw)
+                {
+                    single_translation
+                        .localization_value
+                        .language_translation
+                        .insert(language.to_string(), translation);
+                }
+            }
+
+            Ok(not_found)
+        }
+    }
+
+    /// Converts an output substitution back to the xcstrings input representation
+    pub(crate) fn input_substitution(substitution: &Substitution) -> input::Substitution {
+        let TranslationTypeContainer::Variation(container) =
+            input_container(&Translation::PluralVariation(substitution.plural.clone()))
+        else {
+            unreachable!("A plural is converted to a variation");
+        };
+
+        input::Substitution {
+            arg_num: substitution.arg_num,
+            format_specifier: substitution.format_specifier.to_string(),
+            variations: container.variations,
+        }
+    }
+
+    /// Converts an output translation back to the xcstrings input representation
+    pub(crate) fn input_container(translation: &Translation) -> TranslationTypeContainer {
+        match translation {
+            Translation::Localization(l) => {
+                TranslationTypeContainer::StringUnit(StringUnitContainer {
+                    string_unit: l.clone(),
+                    ..Default::default()
+                })
+            }
+            Translation::PluralVariation(pv) => {
+                let mut container = VariationContainer::default();
+
+                for single in pv {
+                    let unit = Some(StringUnitContainer {
+                        string_unit: single.translation_value.clone(),
+                        ..Default::default()
+                    });
+                    let plural = &mut container.variations.plural;
+
+                    match single.variate {
+                        PluralVariate::Zero => plural.zero = unit,
+                        PluralVariate::One => plural.one = unit,
+                        PluralVariate::Two => plural.two = unit,
+                        PluralVariate::Few => plural.few = unit,
+                        PluralVariate::Many => plural.many = unit,
+                        PluralVariate::Other => plural.other = unit,
+                    }
+                }
+
+                TranslationTypeContainer::Variation(container)
+            }
+            Translation::DeviceVariation(dv) => {
+                let mut container = VariationContainer::default();
+                let device = container
+                    .variations
+                    .device
+                    .get_or_insert_with(Default::default);
+
+                for single in dv {
+                    let unit = Some(DeviceValue::StringUnit(StringUnitContainer {
+                        string_unit: single.translation_value.clone(),
+                        ..Default::default()
+                    }));
+
+                    match single.variate {
+                        DeviceVariate::IPhone => device.iphone = unit,
+                        DeviceVariate::IPad => device.ipad = unit,
+                        DeviceVariate::Mac => device.mac = unit,
+                        DeviceVariate::AppleTv => device.appletv = unit,
+                        DeviceVariate::Watch => device.watch = unit,
+                        DeviceVariate::AppleVision => device.applevision = unit,
+                        DeviceVariate::Other => device.other = unit,
+                    }
+                }
+
+                TranslationTypeContainer::Variation(container)
+            }
+        }
+    }
+}
+
+pub mod diff {
+    use crate::prelude::*;
+    use crate::types::output::{Localizable, ParseConfig, Parsed, ParsedError, State, Translation};
+    use alloc::collections::BTreeMap;
+
+    /// The differences between two versions of a catalog
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct LocalizableDiff {
+        /// Keys only in the new version with their source value
+        pub added: Vec<(String, String)>,
+        /// Keys only in the old version
+        pub removed: Vec<String>,
+        pub changed: Vec<ChangedTranslation>,
+    }
+
+    /// A translation of a key which exists in both versions that was added, removed or edited
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct ChangedTranslation {
+        pub key_raw: String,
+        pub language: String,
+        pub old: Option<Translation>,
+        pub new: Option<Translation>,
+    }
+
+    impl Localizable {
+        pub fn diff(&self, new: &Localizable) -> LocalizableDiff {
+            let mut diff = LocalizableDiff::default();
+
+            for old_translation in &self.single_translation {
+                if !new
+                    .single_translation
+                    .iter()
+                    .any(|st| st.key_raw == old_translation.key_raw)
+                {
+                    diff.removed.push(old_translation.key_raw.to_string());
+                }
+            }
+
+            for new_translation in &new.single_translation {
+                let Some(old_translation) = self
+                    .single_translation
+                    .iter()
+                    .find(|st| st.key_raw == new_translation.key_raw)
+                else {
+                    diff.added.push((
+                        new_translation.key_raw.to_string(),
+                        new_translation
+                            .translation_for(&new.source_language)
+                            .map(Translation::display_value)
+                            .unwrap_or_default(),
+                    ));
+
+                    continue;
+                };
+
+                let old_languages = &old_translation.localization_value.language_translation;
+                let new_languages = &new_translation.localization_value.language_translation;
+                let mut languages: Vec<_> =
+                    old_languages.keys().chain(new_languages.keys()).collect();
+
+                languages.sort();
+                languages.dedup();
+
+                for language in languages {
+                    let old = old_languages.get(language);
+                    let new = new_languages.get(language);
+
+                    if old != new {
+                        diff.changed.push(ChangedTranslation {
+                            key_raw: new_translation.key_raw.to_string(),
+                            language: language.to_string(),
+                            old: old.cloned(),
+                            new: new.cloned(),
+                        });
+                    }
+                }
+            }
+
+            diff
+        }
+    }
+
+    fn markdown_cell(value: &str) -> String {
+        value.replace('|', "\\|").replace('\n', "<br>")
+    }
+
+    impl LocalizableDiff {
+        pub fn is_empty(&self) -> bool {
+            self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+        }
+
+        /// Renders the added, removed and changed keys as Markdown tables, empty sections are omitted
+        pub fn to_markdown(&self) -> String {
+            let mut lines = vec![];
+
+            if !self.added.is_empty() {
+                lines.push("## Added".to_string());
+                lines.push(String::new());
+                lines.push("| Key | Source |".to_string());
+                lines.push("| --- | --- |".to_string());
+
+                for (key, source) in &self.added {
+                    lines.push(format!(
+                        "| {} | {} |",
+                        markdown_cell(key),
+                        markdown_cell(source)
+                    ));
+                }
+
+                lines.push(String::new());
+            }
+
+            if !self.removed.is_empty() {
+                lines.push("## Removed".to_string());
+                lines.push(String::new());
+                lines.push("| Key |".to_string());
+                lines.push("| --- |".to_string());
+
+                for key in &self.removed {
+                    lines.push(format!("| {} |", markdown_cell(key)));
+                }
+
+                lines.push(String::new());
+            }
+
+            if !self.changed.is_empty() {
+                lines.push("## Changed".to_string());
+                lines.push(String::new());
+                lines.push("| Key | Language | Old | New |".to_string());
+                lines.push("| --- | --- | --- | --- |".to_string());
+
+                for changed in &self.changed {
+                    let value = |t: &Option<Translation>| {
+                        t.as_ref()
+                            .map(Translation::display_value)
+                            .unwrap_or_default()
+                    };
+
+                    lines.push(format!(
+                        "| {} | {} | {} | {} |",
+                        markdown_cell(&changed.key_raw),
+                        markdown_cell(&changed.language),
+                        markdown_cell(&value(&changed.old)),
+                        markdown_cell(&value(&changed.new))
+                    ));
+                }
+
+                lines.push(String::new());
+            }
+
+            lines.join("\n")
+        }
+    }
+
+    /// Why [Parsed::is_roundtrip_stable] failed
+    #[derive(Debug, Clone)]
+    pub enum RoundtripError {
+        /// The serialized catalog couldn't be parsed again
+        Parse(ParsedError),
+        /// The catalog parsed from the serialized version differs
+        Changed(LocalizableDiff),
+    }
+
+    impl Parsed {
+        /// Saves [Parsed::localizable] with [Localizable::to_xcstrings_with_state_names], parses
+        /// it again with `config` (the config it was parsed with) and compares the two
+        pub fn is_roundtrip_stable(&self, config: &ParseConfig) -> Result<(), RoundtripError> {
+            let json = self
+                .localizable
+                .to_xcstrings_with_state_names(&config.state_names);
+            let reparsed = crate::parse_from_string_with_config(json, config)
+                .map_err(RoundtripError::Parse)?;
+            let diff = self.localizable.diff(&reparsed.localizable);
+
+            if diff.is_empty()
+                && self.localizable.source_language == reparsed.localizable.source_language
+            {
+                Ok(())
+            } else {
+                Err(RoundtripError::Changed(diff))
+            }
+        }
+    }
+
+    /// Word counts of a single language between two versions, see [word_count_delta]
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct WordDelta {
+        /// Words of translations only in the new version
+        pub added: usize,
+        /// Words of translations only in the old version
+        pub removed: usize,
+        /// Words (in the new version) of translations whose value changed
+        pub changed: usize,
+    }
+
+    /// Compares the values of `language` per key, e.g. to estimate the cost of a vendor update.
+    /// State-only changes aren't counted.
+    pub fn word_count_delta(old: &Localizable, new: &Localizable, language: &str) -> WordDelta {
+        let mut delta = WordDelta::default();
+        let old_index = old.build_index();
+        let new_index = new.build_index();
+        let values = |translation: &Translation| -> Vec<String> {
+            translation
+                .translation_values()
+                .into_iter()
+                .map(|tv| tv.value.to_string())
+                .collect()
+        };
+
+        for new_translation in &new.single_translation {
+            let Some(new_value) = new_translation.translation_for(language) else {
+                continue;
+            };
+
+            match old_index
+                .get(&new_translation.key_raw)
+                .and_then(|st| st.translation_for(language))
+            {
+                None => delta.added += new_value.word_count(),
+                Some(old_value) if values(old_value) != values(new_value) => {
+                    delta.changed += new_value.word_count()
+                }
+                Some(_) => {}
+            }
+        }
+
+        for old_translation in &old.single_translation {
+            let Some(old_value) = old_translation.translation_for(language) else {
+                continue;
+            };
+
+            if new_index
+                .get(&old_translation.key_raw)
+                .and_then(|st| st.translation_for(language))
+                .is_none()
+            {
+                delta.removed += old_value.word_count();
+            }
+        }
+
+        delta
+    }
+
+    /// Per language how many values moved from one state to another, see
+    /// [Localizable::state_transitions]
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct StateTransitionReport {
+        pub transitions: BTreeMap<String, BTreeMap<(State, State), usize>>,
+    }
+
+    impl StateTransitionReport {
+        pub fn count(&self, language: &str, from: State, to: State) -> usize {
+            self.transitions
+                .get(language)
+                .and_then(|t| t.get(&(from, to)))
+                .copied()
+                .unwrap_or_default()
+        }
+
+        /// A table per language, e.g. for a standup note
+        pub fn to_markdown(&self) -> String {
+            let mut lines = vec![
+                "| Language | From | To | Count |".to_string(),
+                "| --- | --- | --- | --- |".to_string(),
+            ];
+
+            for (language, transitions) in &self.transitions {
+                for ((from, to), count) in transitions {
+                    lines.push(format!(
+                        "| {} | {} | {} | {} |",
+                        markdown_cell(language),
+                        from.apple_name(),
+                        to.apple_name(),
+                        count
+                    ));
+                }
+            }
+
+            lines.join("\n")
+        }
+    }
+
+    impl Localizable {
+        /// Compares the state of every value which exists in both versions, plural cases one by
+        /// one. Values with an unchanged or unknown state aren't counted.
+        pub fn state_transitions(&self, new: &Localizable) -> StateTransitionReport {
+            let mut report = StateTransitionReport::default();
+            let new_index = new.build_index();
+
+            for old_translation in &self.single_translation {
+                let Some(new_translation) = new_index.get(&old_translation.key_raw) else {
+                    continue;
+                };
+
+                for (language, old) in &old_translation.localization_value.language_translation {
+                    let Some(new) = new_translation.translation_for(language) else {
+                        continue;
+                    };
+                    let pairs: Vec<_> = match (old, new) {
+                        (Translation::Localization(old), Translation::Localization(new)) => {
+                            vec![(old, new)]
+                        }
+                        (Translation::PluralVariation(old), Translation::PluralVariation(new)) => {
+                            old.iter()
+                                .filter_map(|o| {
+                                    new.iter()
+                                        .find(|n| n.variate == o.variate)
+                                        .map(|n| (&o.translation_value, &n.translation_value))
+                                })
+                                .collect()
+                        }
+                        _ => vec![],
+                    };
+
+                    for (old, new) in pairs {
+                        let (Some(from), Some(to)) = (
+                            State::from_apple_name(&old.state),
+                            State::from_apple_name(&new.state),
+                        ) else {
+                            continue;
+                        };
+
+                        if from != to {
+                            *report
+                                .transitions
+                                .entry(language.to_string())
+                                .or_default()
+                                .entry((from, to))
+                                .or_default() += 1;
+                        }
+                    }
+                }
+            }
+
+            report
+        }
+    }
+}
+
+pub mod merge {
+    use crate::prelude::*;
+    use crate::types::output::{Localizable, PluralVariate, Translation};
+
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub enum MergeStrategy {
+        /// Incoming values replace existing values
+        #[default]
+        Overwrite,
+        /// Incoming values are only used where the existing value is missing or empty
+        OnlyFillEmpty,
+    }
+
+    /// A plural case which didn't exist yet for the key and language
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct NewPluralCase {
+        pub key_raw: String,
+        pub language: String,
+        pub variate: PluralVariate,
+    }
+
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct MergeReport {
+        pub added_keys: Vec<String>,
+        pub new_plural_cases: Vec<NewPluralCase>,
+    }
+
+    impl Localizable {
+        /// Merges `incoming` into this catalog. Plural cases are merged one by one, so an
+        /// existing case is never dropped, cases new to a key are listed in the report.
+        pub fn merge(&mut self, incoming: &Localizable, strategy: MergeStrategy) -> MergeReport {
+            let mut report = MergeReport::default();
+
+            for incoming_translation in &incoming.single_translation {
+                let Some(existing) = self
+                    .single_translation
+                    .iter_mut()
+                    .find(|st| st.key_raw == incoming_translation.key_raw)
+                else {
+                    report
+                        .added_keys
+                        .push(incoming_translation.key_raw.to_string());
+                    self.single_translation.push(incoming_translation.clone());
+
+                    continue;
+                };
+
+                for (language, translation) in
+                    &incoming_translation.localization_value.language_translation
+                {
+                    let languages = &mut existing.localization_value.language_translation;
+                    let Some(current) = languages.get_mut(language) else {
+                        languages.insert(language.to_string(), translation.clone());
+
+                        continue;
+                    };
+
+                    match (current, translation) {
+                        (Translation::Localization(current), Translation::Localization(new)) => {
+                            if strategy == MergeStrategy::Overwrite || current.value.is_empty() {
+                                *current = new.clone();
+                            }
+                        }
+                        (
+                            Translation::PluralVariation(current),
+                            Translation::PluralVariation(new),
+                        ) => {
+                            for new_case in new {
+                                match current.iter_mut().find(|c| c.variate == new_case.variate) {
+                                    Some(case) => {
+                                        if strategy == MergeStrategy::Overwrite
+                                            || case.translation_value.value.is_empty()
+                                        {
+                                            case.translation_value =
+                                                new_case.translation_value.clone();
+                                        }
+                                    }
+                                    None => {
+                                        report.new_plural_cases.push(NewPluralCase {
+                                            key_raw: existing.key_raw.to_string(),
+                                            language: language.to_string(),
+                                            variate: new_case.variate.clone(),
+                                        });
+                                        current.push(new_case.clone());
+                                    }
+                                }
+                            }
+
+                            current.sort_by(|a, b| a.variate.cmp(&b.variate));
+                        }
+                        (current, new) => {
+                            if strategy == MergeStrategy::Overwrite {
+                                *current = new.clone();
+                            }
+                        }
+                    }
+                }
+            }
+
+            self.single_translation
+                .sort_by(|a, b| a.key_raw.cmp(&b.key_raw));
+
+            report
+        }
+    }
+}
+
+pub mod visit {
+    use crate::types::inoutoutput::TranslationValue;
+    use crate::types::output::{
+        Localizable, SingleDeviceVariation, SinglePluralVariation, SingleTranslation, Translation,
+    };
+
+    /// Called for every translation by [Localizable::accept], the methods default to doing nothing
+    pub trait TranslationVisitor {
+        fn visit_localization(
+            &mut self,
+            _single_translation: &SingleTranslation,
+            _language: &str,
+            _value: &TranslationValue,
+        ) {
+        }
+
+        fn visit_plural(
+            &mut self,
+            _single_translation: &SingleTranslation,
+            _language: &str,
+            _cases: &[SinglePluralVariation],
+        ) {
+        }
+
+        fn visit_device(
+            &mut self,
+            _single_translation: &SingleTranslation,
+            _language: &str,
+            _devices: &[SingleDeviceVariation],
+        ) {
+        }
+    }
+
+    impl Localizable {
+        /// Walks the keys in order and per key the languages in order
+        pub fn accept(&self, visitor: &mut impl TranslationVisitor) {
+            for single_translation in &self.single_translation {
+                for (language, translation) in
+                    &single_translation.localization_value.language_translation
+                {
+                    match translation {
+                        Translation::Localization(l) => {
+                            visitor.visit_localization(single_translation, language, l)
+                        }
+                        Translation::PluralVariation(pv) => {
+                            visitor.visit_plural(single_translation, language, pv)
+                        }
+                        Translation::DeviceVariation(dv) => {
+                            visitor.visit_device(single_translation, language, dv)
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub mod pseudo {
+    use crate::prelude::*;
+    use crate::types::inoutoutput::TranslationValue;
+    use crate::types::output::{
+        Localizable, SingleDeviceVariation, SinglePluralVariation, Translation,
+    };
+
+    /// Right-to-left mark, right-to-left override and pop directional formatting
+    const RLM: char = '\u{200F}';
+    const RLO: char = '\u{202E}';
+    const PDF: char = '\u{202C}';
+
+    /// The pseudo-locales Android knows, see
+    /// <https://developer.android.com/guide/topics/resources/pseudolocales>
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum PseudoLocale {
+        /// Accented letters in brackets, catches hardcoded and truncated strings
+        Accented,
+        /// Every word forced right-to-left, catches layouts which don't mirror
+        Bidi,
+    }
+
+    impl PseudoLocale {
+        pub fn language_code(&self) -> &'static str {
+            match self {
+                PseudoLocale::Accented => "en-XA",
+                PseudoLocale::Bidi => "ar-XB",
+            }
+        }
+
+        /// Transforms the text of `value`, placeholders are kept as written
+        pub fn apply(&self, value: &str) -> String {
+            let text = crate::specifier::map_text(value, |text| match self {
+                PseudoLocale::Accented => text.chars().map(accented).collect(),
+                PseudoLocale::Bidi => text
+                    .split(' ')
+                    .map(|word| {
+                        if word.is_empty() {
+                            String::new()
+                        } else {
+                            format!("{RLM}{RLO}{word}{PDF}{RLM}")
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            });
+
+            match self {
+                PseudoLocale::Accented => format!("[{text}]"),
+                // Mirrored in a right-to-left layout, so these show up as `[...]`
+                PseudoLocale::Bidi => format!("]{text}["),
+            }
+        }
+    }
+
+    fn accented(c: char) -> char {
+        match c {
+            'a' => 'á',
+            'c' => 'ç',
+            'e' => 'é',
+            'i' => 'í',
+            'n' => 'ñ',
+            'o' => 'ó',
+            'u' => 'ú',
+            'y' => 'ý',
+            'A' => 'Å',
+            'C' => 'Ç',
+            'E' => 'É',
+            'I' => 'Î',
+            'N' => 'Ñ',
+            'O' => 'Ö',
+            'U' => 'Ü',
+            'Y' => 'Ý',
+            c => c,
+        }
+    }
+
+    impl Localizable {
+        /// Adds [PseudoLocale::language_code] as a language, generated from the source values.
+        /// An existing translation for that language is replaced.
+        pub fn add_pseudolocale(&mut self, pseudo_locale: PseudoLocale) {
+            let value = |tv: &TranslationValue| TranslationValue {
+                value: pseudo_locale.apply(&tv.value),
+                state: crate::TRANSLATED_STATE.to_string(),
+            };
+
+            for single_translation in &mut self.single_translation {
+                let Some(source) = single_translation.translation_for(&self.source_language) else {
+                    continue;
+                };
+                let pseudo = match source {
+                    Translation::Localization(l) => Translation::Localization(value(l)),
+                    Translation::PluralVariation(pv) => Translation::PluralVariation(
+                        pv.iter()
+                            .map(|single| SinglePluralVariation {
+                                variate: single.variate.clone(),
+                                translation_value: value(&single.translation_value),
+                            })
+                            .collect(),
+                    ),
+                    Translation::DeviceVariation(dv) => Translation::DeviceVariation(
+                        dv.iter()
+                            .map(|single| SingleDeviceVariation {
+                                variate: single.variate,
+                                translation_value: value(&single.translation_value),
+                            })
+                            .collect(),
+                    ),
+                };
+
+                single_translation
+                    .localization_value
+                    .language_translation
+                    .insert(pseudo_locale.language_code().to_string(), pseudo);
+            }
+        }
+    }
+}
+
+pub mod validate {
+    use crate::prelude::*;
+    use crate::types::output::{
+        Localizable, LocalizedPerLanguage, PluralVariate, SinglePluralVariation, Translation,
+    };
+    use alloc::collections::{BTreeMap, BTreeSet};
+
+    /// See [Localizable::comment_as_value_suspects]
+    pub const COMMENT_AS_VALUE_MIN_WORDS: usize = 4;
+
+    /// Languages without letter case, skipped by [Localizable::capitalization_mismatches]
+    pub const DEFAULT_CASELESS_LANGUAGES: &[&str] = &[
+        "ar", "fa", "he", "hi", "ja", "ka", "ko", "th", "ur", "zh", "zh-Hans", "zh-Hant",
+    ];
+
+    /// The punctuation compared by [Localizable::trailing_punctuation_mismatches]
+    pub const DEFAULT_TRAILING_PUNCTUATION: &[char] = &['.', '!', '?', ':', '…'];
+
+    /// A sensible threshold for [Localizable::oversized_translations]
+    pub const DEFAULT_MAX_TRANSLATION_CHARS: usize = 2000;
+
+    /// The plural cases which must show the count, see [Localizable::plural_count_omissions].
+    /// `zero`, `one` and `two` can be written out, like `One item`.
+    pub const DEFAULT_COUNT_REQUIRED: &[PluralVariate] = &[
+        PluralVariate::Few,
+        PluralVariate::Many,
+        PluralVariate::Other,
+    ];
+
+    /// Control characters other than tab and newline
+    pub fn is_disallowed_control_character(c: char) -> bool {
+        c.is_control() && c != '\t' && c != '\n'
+    }
+
+    /// A translation which doesn't use exactly the argument positions `1..=n` of the source
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct PositionIssue {
+        pub key_raw: String,
+        pub language: String,
+        pub expected: BTreeSet<usize>,
+        pub actual: BTreeSet<usize>,
+    }
+
+    impl PositionIssue {
+        /// Positions below the highest used position which aren't used
+        pub fn gaps(&self) -> Vec<usize> {
+            let max = self.actual.iter().max().copied().unwrap_or_default();
+
+            (1..=max).filter(|p| !self.actual.contains(p)).collect()
+        }
+
+        /// Positions which are used but aren't in the source
+        pub fn extras(&self) -> Vec<usize> {
+            self.actual.difference(&self.expected).copied().collect()
+        }
+    }
+
+    /// The positions used by a translation, for plurals the union of every case
+    pub(crate) fn translation_positions(translation: &Translation) -> BTreeSet<usize> {
+        translation
+            .translation_values()
+            .into_iter()
+            .flat_map(|tv| crate::specifier::argument_positions(&tv.value))
+            .collect()
+    }
+
+    /// Keys translated in one language but not in the other
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct CoverageDiff {
+        pub only_in_a: Vec<String>,
+        pub only_in_b: Vec<String>,
+    }
+
+    impl CoverageDiff {
+        pub fn is_equal(&self) -> bool {
+            self.only_in_a.is_empty() && self.only_in_b.is_empty()
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum KeyParityKind {
+        /// The source has the key, the language doesn't
+        Missing,
+        /// The language has the key, the source doesn't
+        Extra,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct KeyParityIssue {
+        pub key_raw: String,
+        pub language: String,
+        pub kind: KeyParityKind,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct ControlCharacterIssue {
+        pub key_raw: String,
+        pub language: String,
+        pub code_point: u32,
+    }
+
+    /// A plural with the same category more than once, Android would silently drop one of them
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct DuplicatePluralCase {
+        pub key_raw: String,
+        pub language: String,
+        pub variate: PluralVariate,
+    }
+
+    /// The categories which are used more than once. Parsing can't produce these, but a plural
+    /// which is edited programmatically can.
+    pub fn duplicate_plural_cases(plural: &[SinglePluralVariation]) -> Vec<PluralVariate> {
+        let mut seen = BTreeSet::new();
+        let mut duplicates = BTreeSet::new();
+
+        for single in plural {
+            if !seen.insert(single.variate.clone()) {
+                duplicates.insert(single.variate.clone());
+            }
+        }
+
+        duplicates.into_iter().collect()
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum TranslationKind {
+        Localization,
+        PluralVariation,
+    }
+
+    impl TranslationKind {
+        pub fn of(translation: &Translation) -> Self {
+            match translation {
+                // Android emits a device variation as a plain string
+                Translation::Localization(_) | Translation::DeviceVariation(_) => {
+                    TranslationKind::Localization
+                }
+                Translation::PluralVariation(_) => TranslationKind::PluralVariation,
+            }
+        }
+    }
+
+    /// An argument position which isn't the same [crate::specifier::ArgumentType] in every
+    /// case of a plural
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct PluralTypeConflict {
+        pub key_raw: String,
+        pub language: String,
+        pub position: usize,
+        pub types: Vec<(PluralVariate, crate::specifier::ArgumentType)>,
+    }
+
+    /// A plural case which doesn't show the count while its category requires it
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct PluralCountOmission {
+        pub key_raw: String,
+        pub language: String,
+        pub variate: PluralVariate,
+    }
+
+    /// Any issue reported by [Localizable::validate_each]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum ValidationIssue {
+        ControlCharacter(ControlCharacterIssue),
+        Oversized(OversizedTranslation),
+        Multiline(MultilineTranslation),
+        Typography(TypographyIssue),
+        PluralCountOmission(PluralCountOmission),
+        Position(PositionIssue),
+        MixedKind(MixedKindIssue),
+        PluralTypeConflict(PluralTypeConflict),
+        Capitalization(CapitalizationMismatch),
+        TrailingPunctuation(TrailingPunctuationMismatch),
+        RawKeyInTranslation {
+            key_raw: String,
+            language: String,
+        },
+        /// The raw key, see [Localizable::comment_as_value_suspects]
+        CommentAsValue(String),
+        /// The raw key, see [Localizable::empty_source_values]
+        EmptySourceValue(String),
+        /// The raw key, see [Localizable::digit_leading_android_keys]
+        DigitLeadingAndroidKey(String),
+    }
+
+    /// Which validators [Localizable::validate_each] runs, `None`/`false` skips one
+    #[derive(Debug, Clone, Copy)]
+    pub struct ValidationConfig<'a> {
+        pub control_characters: bool,
+        pub max_translation_chars: Option<usize>,
+        pub max_lines: Option<usize>,
+        pub typography: Option<TypographyChecks>,
+        pub count_required: Option<&'a [PluralVariate]>,
+        pub positional_specifiers: bool,
+        pub mixed_translation_kinds: bool,
+        pub plural_type_conflicts: bool,
+        /// The languages skipped by [Localizable::capitalization_mismatches]
+        pub caseless_languages: Option<&'a [&'a str]>,
+        pub trailing_punctuation: Option<&'a [char]>,
+        pub raw_key_in_translation: bool,
+        pub comment_as_value: bool,
+        pub empty_source_values: bool,
+        pub digit_leading_android_keys: bool,
+    }
+
+    impl Default for ValidationConfig<'_> {
+        fn default() -> Self {
+            Self {
+                control_characters: true,
+                max_translation_chars: Some(DEFAULT_MAX_TRANSLATION_CHARS),
+                max_lines: None,
+                typography: Some(TypographyChecks::default()),
+                count_required: Some(DEFAULT_COUNT_REQUIRED),
+                positional_specifiers: true,
+                mixed_translation_kinds: true,
+                plural_type_conflicts: true,
+                caseless_languages: Some(DEFAULT_CASELESS_LANGUAGES),
+                trailing_punctuation: Some(DEFAULT_TRAILING_PUNCTUATION),
+                raw_key_in_translation: true,
+                comment_as_value: true,
+                empty_source_values: true,
+                digit_leading_android_keys: true,
+            }
+        }
+    }
+
+    /// A value with more lines than allowed, see [Localizable::multiline_translations]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct MultilineTranslation {
+        pub key_raw: String,
+        pub language: String,
+        /// For plurals the case with the most lines
+        pub line_count: usize,
+    }
+
+    /// Which checks [Localizable::typography_issues] runs
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct TypographyChecks {
+        /// The same word twice in a row, case-insensitive, e.g. `the The`
+        pub repeated_words: bool,
+        /// Two or more consecutive spaces
+        pub double_spaces: bool,
+    }
+
+    impl Default for TypographyChecks {
+        fn default() -> Self {
+            Self {
+                repeated_words: true,
+                double_spaces: true,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum TypographyIssueKind {
+        /// The repeated word as it appears the second time
+        RepeatedWord(String),
+        DoubleSpace,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct TypographyIssue {
+        pub key_raw: String,
+        pub language: String,
+        pub kind: TypographyIssueKind,
+    }
+
+    fn typography_issue_kinds(value: &str, checks: TypographyChecks) -> Vec<TypographyIssueKind> {
+        let mut kinds = vec![];
+
+        if checks.repeated_words {
+            let mut words = vec![];
+
+            // Only the text counts, a format token like `%lld` separates the words around it
+            crate::specifier::map_text(value, |text| {
+                words.extend(
+                    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+                        .filter(|word| !word.is_empty())
+                        .map(str::to_string),
+                );
+                words.push(String::new());
+
+                text.to_string()
+            });
+
+            for pair in words.windows(2) {
+                if !pair[0].is_empty() && pair[0].to_lowercase() == pair[1].to_lowercase() {
+                    kinds.push(TypographyIssueKind::RepeatedWord(pair[1].to_string()));
+                }
+            }
+        }
+
+        if checks.double_spaces && value.contains("  ") {
+            kinds.push(TypographyIssueKind::DoubleSpace);
+        }
+
+        kinds
+    }
+
+    /// A value longer than the absolute character limit, see [Localizable::oversized_translations]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct OversizedTranslation {
+        pub key_raw: String,
+        pub language: String,
+        /// For plurals the longest case
+        pub char_count: usize,
+    }
+
+    /// A translation whose first letter is upper case while the source's is lower case or the
+    /// other way around
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct CapitalizationMismatch {
+        pub key_raw: String,
+        pub language: String,
+        pub source_uppercase: bool,
+    }
+
+    /// The source and translation end differently, `None` means no checked punctuation
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct TrailingPunctuationMismatch {
+        pub key_raw: String,
+        pub language: String,
+        pub source: Option<char>,
+        pub translation: Option<char>,
+    }
+
+    /// A key which is a plural in some languages and a plain string in others
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct MixedKindIssue {
+        pub key_raw: String,
+        pub kinds: BTreeMap<String, TranslationKind>,
+    }
+
+    impl Localizable {
+        /// Runs the validators of `config` and calls `on_issue` for every issue as it's found,
+        /// instead of collecting them first like the `Vec` returning validators. The issues
+        /// are reported per validator, in the order of [ValidationConfig].
+        ///
+        /// The validators from [ValidationConfig::positional_specifiers] on compare a key
+        /// across its languages, so their issues are reported once that key is checked.
+        /// Reports grouping keys, like
+        /// [Localizable::duplicate_source_values], and the `assert_*` checks aren't included.
+        pub fn validate_each(
+            &self,
+            config: &ValidationConfig,
+            on_issue: &mut dyn FnMut(ValidationIssue),
+        ) {
+            if config.control_characters {
+                self.control_characters_into(&mut |issue| {
+                    on_issue(ValidationIssue::ControlCharacter(issue))
+                });
+            }
+
+            if let Some(max_chars) = config.max_translation_chars {
+                self.oversized_translations_into(max_chars, &mut |issue| {
+                    on_issue(ValidationIssue::Oversized(issue))
+                });
+            }
+
+            if let Some(max_lines) = config.max_lines {
+                self.multiline_translations_into(max_lines, &mut |issue| {
+                    on_issue(ValidationIssue::Multiline(issue))
+                });
+            }
+
+            if let Some(checks) = config.typography {
+                self.typography_issues_into(checks, &mut |issue| {
+                    on_issue(ValidationIssue::Typography(issue))
+                });
+            }
+
+            if let Some(count_required) = config.count_required {
+                self.plural_count_omissions_into(count_required, &mut |issue| {
+                    on_issue(ValidationIssue::PluralCountOmission(issue))
+                });
+            }
+
+            if config.positional_specifiers {
+                self.positional_specifier_issues_into(&mut |issue| {
+                    on_issue(ValidationIssue::Position(issue))
+                });
+            }
+
+            if config.mixed_translation_kinds {
+                self.mixed_translation_kinds_into(&mut |issue| {
+                    on_issue(ValidationIssue::MixedKind(issue))
+                });
+            }
+
+            if config.plural_type_conflicts {
+                self.plural_type_conflicts_into(&mut |issue| {
+                    on_issue(ValidationIssue::PluralTypeConflict(issue))
+                });
+            }
+
+            if let Some(caseless_languages) = config.caseless_languages {
+                self.capitalization_mismatches_into(caseless_languages, &mut |issue| {
+                    on_issue(ValidationIssue::Capitalization(issue))
+                });
+            }
+
+            if let Some(punctuation) = config.trailing_punctuation {
+                self.trailing_punctuation_mismatches_into(punctuation, &mut |issue| {
+                    on_issue(ValidationIssue::TrailingPunctuation(issue))
+                });
+            }
+
+            if config.raw_key_in_translation {
+                self.raw_key_in_translation_into(&mut |key_raw, language| {
+                    on_issue(ValidationIssue::RawKeyInTranslation { key_raw, language })
+                });
+            }
+
+            if config.comment_as_value {
+                self.comment_as_value_suspects_into(&mut |key_raw| {
+                    on_issue(ValidationIssue::CommentAsValue(key_raw))
+                });
+            }
+
+            if config.empty_source_values {
+                self.empty_source_values_into(&mut |key_raw| {
+                    on_issue(ValidationIssue::EmptySourceValue(key_raw))
+                });
+            }
+
+            if config.digit_leading_android_keys {
+                self.digit_leading_android_keys_into(&mut |key_raw| {
+                    on_issue(ValidationIssue::DigitLeadingAndroidKey(key_raw))
+                });
+            }
+        }
+
+        /// Reports every C0/C1 control character (except tab and newline) found in the values
+        pub fn control_characters(&self) -> Vec<ControlCharacterIssue> {
+            let mut issues = vec![];
+
+            self.control_characters_into(&mut |issue| issues.push(issue));
+
+            issues
+        }
+
+        fn control_characters_into(&self, on_issue: &mut dyn FnMut(ControlCharacterIssue)) {
+            for single_translation in &self.single_translation {
+                for (language, translation) in
+                    &single_translation.localization_value.language_translation
+                {
+                    for translation_value in translation.translation_values() {
+                        for c in translation_value
+                            .value
+                            .chars()
+                            .filter(|c| is_disallowed_control_character(*c))
+                        {
+                            on_issue(ControlCharacterIssue {
+                                key_raw: single_translation.key_raw.to_string(),
+                                language: language.to_string(),
+                                code_point: c as u32,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        /// Heuristic for text placed in the comment instead of the value: identifier-like keys
+        /// (no whitespace) whose source value is empty or the key itself, while the comment is
+        /// a sentence of at least [COMMENT_AS_VALUE_MIN_WORDS] words.
+        pub fn comment_as_value_suspects(&self) -> Vec<String> {
+            let mut suspects = vec![];
+
+            self.comment_as_value_suspects_into(&mut |key_raw| suspects.push(key_raw));
+
+            suspects
+        }
+
+        fn comment_as_value_suspects_into(&self, on_issue: &mut dyn FnMut(String)) {
+            self.single_translation
+                .iter()
+                .filter(|st| {
+                    let comment = st.comment.trim();
+                    let sentence_like = comment.split_whitespace().count()
+                        >= COMMENT_AS_VALUE_MIN_WORDS
+                        && (comment.starts_with(char::is_uppercase)
+                            || comment.ends_with(['.', '!', '?']));
+                    let value_missing = match st.translation_for(&self.source_language) {
+                        Some(Translation::Localization(l)) => {
+                            l.value.trim().is_empty() || l.value == st.key_raw
+                        }
+                        Some(Translation::PluralVariation(_) | Translation::DeviceVariation(_)) => {
+                            false
+                        }
+                        None => true,
+                    };
+
+                    !st.key_raw.contains(char::is_whitespace) && value_missing && sentence_like
+                })
+                .for_each(|st| on_issue(st.key_raw.to_string()));
+        }
+
+        /// Keys whose translations aren't all the same [TranslationKind], for Android these end up
+        /// as a `<plurals>` in one language and a `<string>` in another
+        pub fn mixed_translation_kinds(&self) -> Vec<MixedKindIssue> {
+            let mut issues = vec![];
+
+            self.mixed_translation_kinds_into(&mut |issue| issues.push(issue));
+
+            issues
+        }
+
+        fn mixed_translation_kinds_into(&self, on_issue: &mut dyn FnMut(MixedKindIssue)) {
+            self.single_translation
+                .iter()
+                .filter_map(|st| {
+                    let kinds: BTreeMap<_, _> = st
+                        .localization_value
+                        .language_translation
+                        .iter()
+                        .map(|(language, translation)| {
+                            (language.to_string(), TranslationKind::of(translation))
+                        })
+                        .collect();
+                    let mut distinct = kinds.values();
+                    let first = distinct.next()?;
+
+                    distinct.any(|kind| kind != first).then(|| MixedKindIssue {
+                        key_raw: st.key_raw.to_string(),
+                        kinds,
+                    })
+                })
+                .for_each(on_issue);
+        }
+
+        /// Compares the argument types per position across the cases of every plural, this is
+        /// stricter than [Localizable::positional_specifier_issues] which only checks positions
+        pub fn plural_type_conflicts(&self) -> Vec<PluralTypeConflict> {
+            let mut conflicts = vec![];
+
+            self.plural_type_conflicts_into(&mut |conflict| conflicts.push(conflict));
+
+            conflicts
+        }
+
+        fn plural_type_conflicts_into(&self, on_issue: &mut dyn FnMut(PluralTypeConflict)) {
+            for single_translation in &self.single_translation {
+                for (language, translation) in
+                    &single_translation.localization_value.language_translation
+                {
+                    let Translation::PluralVariation(plural) = translation else {
+                        continue;
+                    };
+                    let mut per_position: BTreeMap<usize, Vec<_>> = BTreeMap::new();
+
+                    for single in plural {
+                        for (position, argument_type) in
+                            crate::specifier::argument_types(&single.translation_value.value)
+                        {
+                            per_position
+                                .entry(position)
+                                .or_default()
+                                .push((single.variate.clone(), argument_type));
+                        }
+                    }
+
+                    for (position, types) in per_position {
+                        if types.iter().any(|(_, t)| *t != types[0].1) {
+                            on_issue(PluralTypeConflict {
+                                key_raw: single_translation.key_raw.to_string(),
+                                language: language.to_string(),
+                                position,
+                                types,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        /// Translations with more than `max_chars` characters, this often means something was
+        /// pasted by accident. Unlike a length ratio against the source this is an absolute limit.
+        pub fn oversized_translations(&self, max_chars: usize) -> Vec<OversizedTranslation> {
+            let mut oversized = vec![];
+
+            self.oversized_translations_into(max_chars, &mut |issue| oversized.push(issue));
+
+            oversized
+        }
+
+        fn oversized_translations_into(
+            &self,
+            max_chars: usize,
+            on_issue: &mut dyn FnMut(OversizedTranslation),
+        ) {
+            for single_translation in &self.single_translation {
+                for (language, translation) in
+                    &single_translation.localization_value.language_translation
+                {
+                    let char_count = translation
+                        .translation_values()
+                        .iter()
+                        .map(|tv| tv.value.chars().count())
+                        .max()
+                        .unwrap_or_default();
+
+                    if char_count > max_chars {
+                        on_issue(OversizedTranslation {
+                            key_raw: single_translation.key_raw.to_string(),
+                            language: language.to_string(),
+                            char_count,
+                        });
+                    }
+                }
+            }
+        }
+
+        /// Plural cases in `count_required` (e.g. [DEFAULT_COUNT_REQUIRED]) without an integer
+        /// specifier, so the number is never shown
+        pub fn plural_count_omissions(
+            &self,
+            count_required: &[PluralVariate],
+        ) -> Vec<PluralCountOmission> {
+            let mut omissions = vec![];
+
+            self.plural_count_omissions_into(count_required, &mut |issue| omissions.push(issue));
+
+            omissions
+        }
+
+        fn plural_count_omissions_into(
+            &self,
+            count_required: &[PluralVariate],
+            on_issue: &mut dyn FnMut(PluralCountOmission),
+        ) {
+            for single_translation in &self.single_translation {
+                for (language, translation) in
+                    &single_translation.localization_value.language_translation
+                {
+                    let Translation::PluralVariation(pv) = translation else {
+                        continue;
+                    };
+
+                    for single in pv {
+                        let has_count =
+                            crate::specifier::argument_types(&single.translation_value.value)
+                                .iter()
+                                .any(|(_, argument_type)| {
+                                    *argument_type == crate::specifier::ArgumentType::Integer
+                                });
+
+                        if !has_count && count_required.contains(&single.variate) {
+                            on_issue(PluralCountOmission {
+                                key_raw: single_translation.key_raw.to_string(),
+                                language: language.to_string(),
+                                variate: single.variate.clone(),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        /// Translations with more than `max_lines` lines (a `\n` starts a new line), these break
+        /// single line UI components
+        pub fn multiline_translations(&self, max_lines: usize) -> Vec<MultilineTranslation> {
+            let mut multiline = vec![];
+
+            self.multiline_translations_into(max_lines, &mut |issue| multiline.push(issue));
+
+            multiline
+        }
+
+        fn multiline_translations_into(
+            &self,
+            max_lines: usize,
+            on_issue: &mut dyn FnMut(MultilineTranslation),
+        ) {
+            for single_translation in &self.single_translation {
+                for (language, translation) in
+                    &single_translation.localization_value.language_translation
+                {
+                    let line_count = translation
+                        .translation_values()
+                        .iter()
+                        .map(|tv| tv.value.matches('\n').count() + 1)
+                        .max()
+                        .unwrap_or_default();
+
+                    if line_count > max_lines {
+                        on_issue(MultilineTranslation {
+                            key_raw: single_translation.key_raw.to_string(),
+                            language: language.to_string(),
+                            line_count,
+                        });
+                    }
+                }
+            }
+        }
+
+        /// Compares the case of the first character of every plain translation with the source.
+        /// This is lenient: values which don't start with a cased character (digits,
+        /// placeholders, caseless scripts) are skipped, as are the `caseless_languages`. These
+        /// match the whole language or its base language, so `ja` skips `ja-JP` as well.
+        pub fn capitalization_mismatches(
+            &self,
+            caseless_languages: &[&str],
+        ) -> Vec<CapitalizationMismatch> {
+            let mut mismatches = vec![];
+
+            self.capitalization_mismatches_into(caseless_languages, &mut |mismatch| {
+                mismatches.push(mismatch)
+            });
+
+            mismatches
+        }
+
+        fn capitalization_mismatches_into(
+            &self,
+            caseless_languages: &[&str],
+            on_issue: &mut dyn FnMut(CapitalizationMismatch),
+        ) {
+            let first_uppercase = |translation: &Translation| match translation {
+                Translation::Localization(l) => l
+                    .value
+                    .chars()
+                    .next()
+                    .filter(|c| c.is_uppercase() || c.is_lowercase())
+                    .map(char::is_uppercase),
+                Translation::PluralVariation(_) | Translation::DeviceVariation(_) => None,
+            };
+
+            for single_translation in &self.single_translation {
+                let Some(source_uppercase) = single_translation
+                    .translation_for(&self.source_language)
+                    .and_then(first_uppercase)
+                else {
+                    continue;
+                };
+
+                for (language, translation) in
+                    &single_translation.localization_value.language_translation
+                {
+                    let base = language.split(['-', '_']).next().unwrap_or_default();
+
+                    if language == &self.source_language
+                        || caseless_languages.iter().any(|caseless| {
+                            caseless.eq_ignore_ascii_case(language)
+                                || caseless.eq_ignore_ascii_case(base)
+                        })
+                    {
+                        continue;
+                    }
+
+                    if first_uppercase(translation).is_some_and(|u| u != source_uppercase) {
+                        on_issue(CapitalizationMismatch {
+                            key_raw: single_translation.key_raw.to_string(),
+                            language: language.to_string(),
+                            source_uppercase,
+                        });
+                    }
+                }
+            }
+        }
+
+        /// Groups the keys sharing the same plain source value, these could be merged to save
+        /// translation effort. Values shorter than `min_chars` (like `OK`) are ignored.
+        pub fn duplicate_source_values(&self, min_chars: usize) -> BTreeMap<String, Vec<String>> {
+            let mut values: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+            for single_translation in &self.single_translation {
+                if let Some(Translation::Localization(l)) =
+                    single_translation.translation_for(&self.source_language)
+                {
+                    if l.value.chars().count() >= min_chars {
+                        values
+                            .entry(l.value.to_string())
+                            .or_default()
+                            .push(single_translation.key_raw.to_string());
+                    }
+                }
+            }
+
+            values.retain(|_, keys| keys.len() > 1);
+
+            values
+        }
+
+        /// `(key_raw, language)` of the translations containing the raw key, which usually means
+        /// the key was pasted as a placeholder and never replaced. The source is skipped.
+        pub fn raw_key_in_translation(&self) -> Vec<(String, String)> {
+            let mut found = vec![];
+
+            self.raw_key_in_translation_into(&mut |key_raw, language| {
+                found.push((key_raw, language))
+            });
+
+            found
+        }
+
+        fn raw_key_in_translation_into(&self, on_issue: &mut dyn FnMut(String, String)) {
+            for single_translation in &self.single_translation {
+                for (language, translation) in
+                    &single_translation.localization_value.language_translation
+                {
+                    if language != &self.source_language
+                        && translation
+                            .translation_values()
+                            .iter()
+                            .any(|tv| tv.value.contains(&single_translation.key_raw))
+                    {
+                        on_issue(single_translation.key_raw.to_string(), language.to_string());
+                    }
+                }
+            }
+        }
+
+        /// Compares the trailing `punctuation` of every plain translation with the source.
+        /// Full width forms (like `。`) count as their ASCII counterpart, trailing whitespace is
+        /// ignored.
+        pub fn trailing_punctuation_mismatches(
+            &self,
+            punctuation: &[char],
+        ) -> Vec<TrailingPunctuationMismatch> {
+            let mut mismatches = vec![];
+
+            self.trailing_punctuation_mismatches_into(punctuation, &mut |mismatch| {
+                mismatches.push(mismatch)
+            });
+
+            mismatches
+        }
+
+        fn trailing_punctuation_mismatches_into(
+            &self,
+            punctuation: &[char],
+            on_issue: &mut dyn FnMut(TrailingPunctuationMismatch),
+        ) {
+            let trailing = |translation: &Translation| match translation {
+                Translation::Localization(l) => Some(
+                    l.value
+                        .trim_end()
+                        .chars()
+                        .last()
+                        .map(|c| match c {
+                            '。' | '．' => '.',
+                            '！' => '!',
+                            '？' => '?',
+                            '：' => ':',
+                            c => c,
+                        })
+                        .filter(|c| punctuation.contains(c)),
+                ),
+                Translation::PluralVariation(_) | Translation::DeviceVariation(_) => None,
+            };
+
+            for single_translation in &self.single_translation {
+                let Some(source) = single_translation
+                    .translation_for(&self.source_language)
+                    .and_then(trailing)
+                else {
+                    continue;
+                };
+
+                for (language, translation) in
+                    &single_translation.localization_value.language_translation
+                {
+                    if language == &self.source_language {
+                        continue;
+                    }
+
+                    if let Some(translation) = trailing(translation).filter(|t| *t != source) {
+                        on_issue(TrailingPunctuationMismatch {
+                            key_raw: single_translation.key_raw.to_string(),
+                            language: language.to_string(),
+                            source,
+                            translation,
+                        });
+                    }
+                }
+            }
+        }
+
+        /// Scans every value for repeated words and double spaces, see [TypographyChecks]
+        pub fn typography_issues(&self, checks: TypographyChecks) -> Vec<TypographyIssue> {
+            let mut issues = vec![];
+
+            self.typography_issues_into(checks, &mut |issue| issues.push(issue));
+
+            issues
+        }
+
+        fn typography_issues_into(
+            &self,
+            checks: TypographyChecks,
+            on_issue: &mut dyn FnMut(TypographyIssue),
+        ) {
+            for single_translation in &self.single_translation {
+                for (language, translation) in
+                    &single_translation.localization_value.language_translation
+                {
+                    for tv in translation.translation_values() {
+                        for kind in typography_issue_kinds(&tv.value, checks) {
+                            on_issue(TypographyIssue {
+                                key_raw: single_translation.key_raw.to_string(),
+                                language: language.to_string(),
+                                kind,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        /// Groups the keys by trimmed source value, for values which are only equal after
+        /// trimming, e.g. `Continue` and `Continue `. This usually is a copy error.
+        pub fn whitespace_only_value_variants(&self) -> BTreeMap<String, Vec<String>> {
+            let mut groups: BTreeMap<String, Vec<(String, String)>> = BTreeMap::new();
+
+            for single_translation in &self.single_translation {
+                if let Some(Translation::Localization(l)) =
+                    single_translation.translation_for(&self.source_language)
+                {
+                    groups
+                        .entry(l.value.trim().to_string())
+                        .or_default()
+                        .push((single_translation.key_raw.to_string(), l.value.to_string()));
+                }
+            }
+
+            groups
+                .into_iter()
+                .filter(|(_, keys)| keys.iter().any(|(_, value)| value != &keys[0].1))
+                .map(|(trimmed, keys)| (trimmed, keys.into_iter().map(|(key, _)| key).collect()))
+                .collect()
+        }
+
+        /// Keys with an empty source value, for plurals any empty case. A missing source value
+        /// is filled with the key while parsing, so these were explicitly left empty.
+        pub fn empty_source_values(&self) -> Vec<String> {
+            let mut keys = vec![];
+
+            self.empty_source_values_into(&mut |key_raw| keys.push(key_raw));
+
+            keys
+        }
+
+        fn empty_source_values_into(&self, on_issue: &mut dyn FnMut(String)) {
+            self.single_translation
+                .iter()
+                .filter(|st| {
+                    st.translation_for(&self.source_language).is_some_and(|t| {
+                        t.translation_values().iter().any(|tv| tv.value.is_empty())
+                    })
+                })
+                .for_each(|st| on_issue(st.key_raw.to_string()));
+        }
+
+        /// Raw keys whose Android resource name starts with a digit, which Android rejects.
+        /// See [crate::types::output::ParseConfig::leading_digit_prefix] for a fix.
+        pub fn digit_leading_android_keys(&self) -> Vec<String> {
+            let mut keys = vec![];
+
+            self.digit_leading_android_keys_into(&mut |key_raw| keys.push(key_raw));
+
+            keys
+        }
+
+        fn digit_leading_android_keys_into(&self, on_issue: &mut dyn FnMut(String)) {
+            self.single_translation
+                .iter()
+                .filter(|st| {
+                    st.key_alphanumeric
+                        .starts_with(|c: char| c.is_ascii_digit())
+                })
+                .for_each(|st| on_issue(st.key_raw.to_string()));
+        }
+
+        /// The parser falls back to the key when the source value is missing, so every key
+        /// should have a source translation. Returns the keys without one, which indicates
+        /// the tree was modified or built by hand.
+        pub fn assert_source_complete(&self) -> Result<(), Vec<String>> {
+            let missing: Vec<_> = self
+                .single_translation
+                .iter()
+                .filter(|st| st.translation_for(&self.source_language).is_none())
+                .map(|st| st.key_raw.to_string())
+                .collect();
+
+            if missing.is_empty() {
+                Ok(())
+            } else {
+                Err(missing)
+            }
+        }
+
+        /// Checks every target language has exactly the key set of the source language
+        pub fn assert_key_parity(&self) -> Result<(), Vec<KeyParityIssue>> {
+            let mut issues = vec![];
+            let languages = self.languages();
+
+            for single_translation in &self.single_translation {
+                let in_source = single_translation
+                    .translation_for(&self.source_language)
+                    .is_some();
+
+                for language in languages.iter().filter(|l| *l != &self.source_language) {
+                    let kind = match (in_source, single_translation.translation_for(language)) {
+                        (true, None) => KeyParityKind::Missing,
+                        (false, Some(_)) => KeyParityKind::Extra,
+                        _ => continue,
+                    };
+
+                    issues.push(KeyParityIssue {
+                        key_raw: single_translation.key_raw.to_string(),
+                        language: language.to_string(),
+                        kind,
+                    });
+                }
+            }
+
+            if issues.is_empty() {
+                Ok(())
+            } else {
+                Err(issues)
+            }
+        }
+
+        /// Compares which keys are translated in `lang_a` and `lang_b`, e.g. `es` and `es-MX`
+        pub fn coverage_diff(&self, lang_a: &str, lang_b: &str) -> CoverageDiff {
+            let mut diff = CoverageDiff::default();
+
+            for single_translation in &self.single_translation {
+                let translated = |language: &str| {
+                    single_translation
+                        .translation_for(language)
+                        .is_some_and(Translation::is_translated)
+                };
+
+                match (translated(lang_a), translated(lang_b)) {
+                    (true, false) => diff.only_in_a.push(single_translation.key_raw.to_string()),
+                    (false, true) => diff.only_in_b.push(single_translation.key_raw.to_string()),
+                    _ => {}
+                }
+            }
+
+            diff
+        }
+
+        /// Checks every translation uses exactly the argument positions `1..=n` of the source
+        pub fn positional_specifier_issues(&self) -> Vec<PositionIssue> {
+            let mut issues = vec![];
+
+            self.positional_specifier_issues_into(&mut |issue| issues.push(issue));
+
+            issues
+        }
+
+        fn positional_specifier_issues_into(&self, on_issue: &mut dyn FnMut(PositionIssue)) {
+            for single_translation in &self.single_translation {
+                let Some(source) = single_translation.translation_for(&self.source_language) else {
+                    continue;
+                };
+                let expected = translation_positions(source);
+
+                for (language, translation) in
+                    &single_translation.localization_value.language_translation
+                {
+                    if language == &self.source_language {
+                        continue;
+                    }
+
+                    if let Some(issue) = position_issue(
+                        &single_translation.key_raw,
+                        language,
+                        &expected,
+                        translation,
+                    ) {
+                        on_issue(issue);
+                    }
+                }
+            }
+        }
+    }
+
+    impl LocalizedPerLanguage {
+        /// Same as [Localizable::positional_specifier_issues]
+        pub fn positional_specifier_issues(&self) -> Vec<PositionIssue> {
+            let mut issues = vec![];
+            let Some(source) = self.language_localized.get(&self.source_language) else {
+                return issues;
+            };
+            let expected: BTreeMap<_, _> = source
+                .translations
+                .iter()
+                .map(|t| (&t.key_raw, translation_positions(&t.translation)))
+                .collect();
+
+            for (language, info) in &self.language_localized {
+                if language == &self.source_language {
+                    continue;
+                }
+
+                for translation in &info.translations {
+                    if let Some(expected) = expected.get(&translation.key_raw) {
+                        issues.extend(position_issue(
+                            &translation.key_raw,
+                            language,
+                            expected,
+                            &translation.translation,
+                        ));
+                    }
+                }
+            }
+
+            issues
+        }
+    }
+
+    fn position_issue(
+        key_raw: &str,
+        language: &str,
+        expected: &BTreeSet<usize>,
+        translation: &Translation,
+    ) -> Option<PositionIssue> {
+        let actual = translation_positions(translation);
+        let contiguous = actual.iter().copied().eq(1..=actual.len());
+
+        if &actual != expected || !contiguous {
+            Some(PositionIssue {
+                key_raw: key_raw.to_string(),
+                language: language.to_string(),
+                expected: expected.clone(),
+                actual,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Packages the Android resources in an in-memory zip instead of writing them to disk
+#[cfg(feature = "zip")]
+pub mod archive {
+    use crate::prelude::*;
+    use crate::types::output::{DirectoryCasing, LocalizedForAndroid, ParsedError};
+    use std::io::Write;
+
+    impl LocalizedForAndroid {
+        /// The entry paths and contents as they would be written to the `res` directory,
+        /// e.g. `values-nl/strings.xml`, including the grouped files. `file_name` is the name of
+        /// the primary file, pass `AndroidWriteConfig::file_name` to match the disk writer.
+        pub fn entries(
+            &self,
+            source_language: &str,
+            directory_casing: DirectoryCasing,
+            file_name: &str,
+        ) -> Vec<(String, Vec<u8>)> {
+            let mut entries = vec![];
+
+            for (language, content) in &self.sorted_languages {
+                let directory = directory_casing.values_directory(language, source_language);
+
+                entries.push((
+                    format!("{directory}/{file_name}"),
+                    content.as_bytes().to_vec(),
+                ));
+
+                for (file_name, content) in
+                    self.grouped_languages.get(language).into_iter().flatten()
+                {
+                    entries.push((
+                        format!("{directory}/{file_name}"),
+                        content.as_bytes().to_vec(),
+                    ));
+                }
+            }
+
+            entries
+        }
+
+        /// The [LocalizedForAndroid::entries] as zip bytes, the entries are stored without
+        /// compression
+        pub fn to_zip(
+            &self,
+            source_language: &str,
+            directory_casing: DirectoryCasing,
+            file_name: &str,
+        ) -> Result<Vec<u8>, ParsedError> {
+            let to_error = |error: zip::result::ZipError| ParsedError::Io(error.to_string());
+            let options = zip::write::SimpleFileOptions::default()
+                .compression_method(zip::CompressionMethod::Stored);
+            let mut zip = zip::ZipWriter::new(std::io::Cursor::new(vec![]));
+
+            for (name, data) in self.entries(source_language, directory_casing, file_name) {
+                zip.start_file(name, options).map_err(to_error)?;
+                zip.write_all(&data)
+                    .map_err(|error| ParsedError::Io(error.to_string()))?;
+            }
+
+            Ok(zip.finish().map_err(to_error)?.into_inner())
+        }
+    }
+}
+
+pub mod types {
+    pub mod inoutoutput {
+        use crate::prelude::*;
+        use alloc::collections::BTreeMap;
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Debug, Deserialize, Clone, Default, PartialEq, Eq)]
+        pub struct TranslationValue {
+            pub state: String,
+            pub value: String,
+        }
+
+        #[derive(Debug, Serialize, Deserialize, Clone, Default)]
+        pub struct Variation {
+            #[serde(default, skip_serializing_if = "Plural::is_empty")]
+            pub plural: Plural,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub device: Option<Device>,
+        }
+
+        /// A value per Apple device family, `other` is used for the families which aren't listed
+        #[derive(Debug, Serialize, Deserialize, Clone, Default)]
+        pub struct Device {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub iphone: Option<DeviceValue>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub ipad: Option<DeviceValue>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub mac: Option<DeviceValue>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub appletv: Option<DeviceValue>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub watch: Option<DeviceValue>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub applevision: Option<DeviceValue>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub other: Option<DeviceValue>,
+        }
+
+        /// The value of a device, Xcode nests a variation (like a plural per device) here as well
+        #[derive(Debug, Serialize, Deserialize, Clone)]
+        #[serde(untagged)]
+        pub enum DeviceValue {
+            StringUnit(StringUnitContainer),
+            Variation(Box<crate::types::input::VariationContainer>),
+        }
+
+        #[derive(Serialize, Deserialize, Debug, Clone, Default)]
+        pub struct StringUnitContainer {
+            #[serde(rename = "stringUnit")]
+            pub string_unit: TranslationValue,
+            /// The named arguments of the value, like `count` in `%#@count@`
+            #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+            pub substitutions: BTreeMap<String, crate::types::input::Substitution>,
+        }
+
+        #[derive(Debug, Serialize, Deserialize, Clone, Default)]
+        pub struct Plural {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub zero: Option<StringUnitContainer>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub one: Option<StringUnitContainer>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub two: Option<StringUnitContainer>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub other: Option<StringUnitContainer>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub many: Option<StringUnitContainer>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub few: Option<StringUnitContainer>,
+        }
+
+        impl Plural {
+            pub fn is_empty(&self) -> bool {
+                self.zero.is_none()
+                    && self.one.is_none()
+                    && self.two.is_none()
+                    && self.other.is_none()
+                    && self.many.is_none()
+                    && self.few.is_none()
+            }
+        }
+    }
+    pub mod input {
+        use crate::prelude::*;
+        use crate::types::inoutoutput::{StringUnitContainer, TranslationValue, Variation};
+        use crate::{ordered_map, Map};
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Debug, Serialize, Deserialize, Clone)]
+        pub struct Translation {
+            #[allow(dead_code)]
+            #[serde(rename = "sourceLanguage", default)]
+            pub source_language: String,
+            #[serde(serialize_with = "ordered_map")]
+            pub strings: Map<String, Language>,
+            pub version: String,
+        }
+
+        #[derive(Debug, Serialize, Deserialize, Clone)]
+        #[serde(untagged)]
+        #[allow(clippy::large_enum_variant)]
+        pub enum TranslationType {
+            StringUnit(TranslationValue),
+            Variation(Variation),
+        }
+
+        #[derive(Debug, Serialize, Deserialize, Clone, Default)]
+        pub struct VariationContainer {
+            pub variations: Variation,
+        }
+
+        /// A named argument of a value, `%#@name@` is replaced by the matching variation
+        #[derive(Debug, Serialize, Deserialize, Clone, Default)]
+        pub struct Substitution {
+            #[serde(rename = "argNum")]
+            pub arg_num: usize,
+            /// The specifier without `%`, like `lld`
+            #[serde(rename = "formatSpecifier")]
+            pub format_specifier: String,
+            pub variations: Variation,
+        }
+
+        #[derive(Debug, Serialize, Deserialize, Clone)]
+        #[serde(untagged)]
+        #[allow(clippy::large_enum_variant)]
+        pub enum TranslationTypeContainer {
+            StringUnit(StringUnitContainer),
+            Variation(VariationContainer),
+        }
+
+        #[derive(Debug, Serialize, Deserialize, Clone)]
+        pub struct Language {
+            #[serde(default, skip_serializing_if = "String::is_empty")]
+            pub comment: String,
+            /// `false` for keys which must not be translated, absent means `true`
+            #[serde(
+                rename = "shouldTranslate",
+                default,
+                skip_serializing_if = "Option::is_none"
+            )]
+            pub should_translate: Option<bool>,
+            #[serde(
+                serialize_with = "ordered_map",
+                default,
+                skip_serializing_if = "Map::<String, TranslationTypeContainer>::is_empty"
+            )]
+            pub localizations: Map<String, TranslationTypeContainer>,
+        }
+    }
+
+    pub mod output {
+        use crate::types::inoutoutput::TranslationValue;
+        use enum_const_value::EnumConstValue;
+
+        use crate::prelude::*;
+        use alloc::collections::{BTreeMap, BTreeSet};
+        use alloc::string::FromUtf8Error;
+        use core::error::Error;
+        use core::fmt::{Display, Formatter};
+        use serde::{Deserialize, Serialize};
+        #[cfg(feature = "std")]
+        use std::path::PathBuf;
+
+        #[derive(Clone, Debug)]
+        pub enum ParsedError {
+            ParseToJson(String),
+            InvalidUtf8(String),
+            Io(String),
+            InvalidTranslationKey(String),
+            InvalidAndroidXml(String),
+            PlaceholderMismatch(Vec<crate::validate::PositionIssue>),
+            InvalidFileName(String),
+            DuplicatePluralCase(crate::validate::DuplicatePluralCase),
+            InvalidConfig(String),
+            UnknownState(String),
+            /// A variation this crate can't represent, like a plural inside a device variation
+            UnsupportedVariation(String),
+        }
+
+        impl Display for ParsedError {
+            fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+                match self {
+                    ParsedError::ParseToJson(error) => {
+                        write!(f, "Invalid XCStrings file: {}", error)
+                    }
+                    ParsedError::InvalidUtf8(error) => write!(f, "Invalid UTF8: {}", error),
+                    ParsedError::Io(error) => write!(f, "IO error: {}", error),
+                    ParsedError::InvalidTranslationKey(key) => {
+                        write!(f, "Invalid translation key: {}", key)
+                    }
+                    ParsedError::InvalidAndroidXml(error) => {
+                        write!(f, "Invalid Android XML: {}", error)
+                    }
+                    ParsedError::InvalidFileName(file_name) => {
+                        write!(
+                            f,
+                            "Invalid file name, it must be a bare file name ending in .xml: {}",
+                            file_name
+                        )
+                    }
+                    ParsedError::InvalidConfig(error) => write!(f, "Invalid config: {}", error),
+                    ParsedError::UnknownState(state) => write!(f, "Unknown state: {}", state),
+                    ParsedError::UnsupportedVariation(error) => {
+                        write!(f, "Unsupported variation: {}", error)
+                    }
+                    ParsedError::DuplicatePluralCase(issue) => write!(
+                        f,
+                        "Duplicate plural case {} for {} ({})",
+                        issue.variate.android_key(),
+                        issue.key_raw,
+                        issue.language
+                    ),
+                    ParsedError::PlaceholderMismatch(issues) => {
+                        write!(f, "Placeholder mismatch:")?;
+
+                        for issue in issues {
+                            write!(
+                                f,
+                                " {} ({}) expected {:?} but found {:?};",
+                                issue.key_raw, issue.language, issue.expected, issue.actual
+                            )?;
+                        }
+
+                        Ok(())
+                    }
+                }
+            }
+        }
+
+        impl Error for ParsedError {
+            fn source(&self) -> Option<&(dyn Error + 'static)> {
+                None
+            }
+        }
+
+        impl From<FromUtf8Error> for ParsedError {
+            fn from(value: FromUtf8Error) -> Self {
+                ParsedError::InvalidUtf8(value.to_string())
+            }
+        }
+
+        #[cfg(feature = "std")]
+        impl From<std::io::Error> for ParsedError {
+            fn from(value: std::io::Error) -> Self {
+                ParsedError::Io(value.to_string())
+            }
+        }
+
+        impl From<serde_json::Error> for ParsedError {
+            fn from(value: serde_json::Error) -> Self {
+                ParsedError::ParseToJson(value.to_string())
+            }
+        }
+
+        pub type ParsedResult = Result<Parsed, ParsedError>;
+
+        #[derive(Debug, Clone, Default)]
+        pub struct ParseConfig {
+            pub state_names: StateNames,
+            /// Removes C0/C1 control characters (except tab and newline) from the values
+            pub strip_control_characters: bool,
+            /// Android resource names can't start with a digit, when set this is prepended to
+            /// such sanitized keys, e.g. `_` turns `1st place` into `_1st_place`
+            pub leading_digit_prefix: Option<String>,
+            /// The source language for files without `sourceLanguage`. When not set, the
+            /// [crate::SOURCE_LANGUAGE_ENV] environment variable is used (only with `std`),
+            /// falling back to [crate::DEFAULT_SOURCE_LANGUAGE].
+            pub default_source_language: Option<String>,
+            /// Fails with [ParsedError::UnknownState] for states which aren't in
+            /// [ParseConfig::state_names], e.g. a typo in a hand-edited file
+            pub strict_states: bool,
+            /// Removed from the start of comments, e.g. [crate::DEFAULT_AUTO_COMMENT_PREFIX] to
+            /// read back generated comments without their marker
+            pub strip_comment_prefix: Option<String>,
+            /// Collapses runs of newlines in the values to at most this many, e.g. 1 turns
+            /// `a\n\n\nb` into `a\nb`. `None` keeps them as they are.
+            pub max_consecutive_newlines: Option<usize>,
+        }
+
+        impl ParseConfig {
+            /// Explicit config > environment variable > hardcoded default
+            pub fn resolve_default_source_language(&self) -> String {
+                #[cfg(feature = "std")]
+                let env = std::env::var(crate::SOURCE_LANGUAGE_ENV).ok();
+                #[cfg(not(feature = "std"))]
+                let env: Option<String> = None;
+
+                self.resolve_default_source_language_with(env.as_deref())
+            }
+
+            /// [ParseConfig::resolve_default_source_language] with `env` as the value of the
+            /// environment variable
+            pub(crate) fn resolve_default_source_language_with(&self, env: Option<&str>) -> String {
+                if let Some(language) = &self.default_source_language {
+                    return language.to_string();
+                }
+
+                match env {
+                    Some(language) if !language.is_empty() => language.to_string(),
+                    _ => crate::DEFAULT_SOURCE_LANGUAGE.to_string(),
+                }
+            }
+        }
+
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum State {
+            Translated,
+            New,
+            NeedsReview,
+        }
+
+        impl State {
+            /// The name Apple uses in xcstrings files
+            pub fn apple_name(&self) -> &'static str {
+                match self {
+                    State::Translated => crate::TRANSLATED_STATE,
+                    State::New => crate::NEW_STATE,
+                    State::NeedsReview => crate::NEEDS_REVIEW_STATE,
+                }
+            }
+
+            pub fn from_apple_name(name: &str) -> Option<Self> {
+                [State::Translated, State::New, State::NeedsReview]
+                    .into_iter()
+                    .find(|state| state.apple_name() == name)
+            }
+        }
+
+        /// The state strings as they appear in the catalog. When parsing, these are mapped to
+        /// the Apple names in the [Localizable], so the rest of the crate only deals with those.
+        /// [Parsed::translation] is left untouched, so serializing it keeps the custom names.
+        /// [Localizable::to_xcstrings_with_state_names] writes them back from the [Localizable].
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct StateNames {
+            pub translated: String,
+            pub new: String,
+            pub needs_review: String,
+        }
+
+        impl Default for StateNames {
+            fn default() -> Self {
+                Self {
+                    translated: crate::TRANSLATED_STATE.to_string(),
+                    new: crate::NEW_STATE.to_string(),
+                    needs_review: crate::NEEDS_REVIEW_STATE.to_string(),
+                }
+            }
+        }
+
+        impl StateNames {
+            pub fn name(&self, state: State) -> &str {
+                match state {
+                    State::Translated => &self.translated,
+                    State::New => &self.new,
+                    State::NeedsReview => &self.needs_review,
+                }
+            }
+
+            pub fn state(&self, name: &str) -> Option<State> {
+                [State::Translated, State::New, State::NeedsReview]
+                    .into_iter()
+                    .find(|state| self.name(*state) == name)
+            }
+
+            /// Maps a custom state name to the Apple name, unknown names are returned as is
+            pub fn normalize(&self, name: &str) -> String {
+                match self.state(name) {
+                    Some(state) => state.apple_name().to_string(),
+                    None => name.to_string(),
+                }
+            }
+
+            /// Maps an Apple state name back to the custom name, unknown names are returned as is
+            pub fn denormalize(&self, name: &str) -> String {
+                match State::from_apple_name(name) {
+                    Some(state) => self.name(state).to_string(),
+                    None => name.to_string(),
+                }
+            }
+        }
+
+        #[derive(Debug, Clone)]
+        pub struct Parsed {
+            pub localizable: Localizable,
+            pub translation: super::input::Translation,
+            /// The string table name, derived from the file name (`Localizable.xcstrings` is
+            /// `Localizable`). Only available when parsed with [crate::parse_from_file].
+            pub table_name: Option<String>,
+        }
+
+        impl Parsed {
+            /// The unprocessed input of `key_raw`, before any fallback or sanitizing
+            pub fn input_for_key(&self, key_raw: &str) -> Option<&super::input::Language> {
+                self.translation.strings.get(key_raw)
+            }
+        }
+
+        #[derive(Debug, Clone)]
+        pub struct Localizable {
+            pub source_language: String,
+            pub single_translation: Vec<SingleTranslation>,
+            /// The `version` of the catalog, written back by [Localizable::to_xcstrings]
+            pub version: String,
+        }
+
+        /// Constant time lookups by raw key, see [Localizable::build_index]
+        #[derive(Debug, Clone)]
+        pub struct LocalizableIndex<'a> {
+            by_key: crate::Map<&'a str, &'a SingleTranslation>,
+        }
+
+        impl<'a> LocalizableIndex<'a> {
+            pub fn contains_key(&self, key_raw: &str) -> bool {
+                self.by_key.contains_key(key_raw)
+            }
+
+            pub fn get(&self, key_raw: &str) -> Option<&'a SingleTranslation> {
+                self.by_key.get(key_raw).copied()
+            }
+
+            pub fn len(&self) -> usize {
+                self.by_key.len()
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.by_key.is_empty()
+            }
+        }
+
+        #[derive(Debug, Clone)]
+        pub struct SingleTranslation {
+            pub key_raw: String,
+            pub key_alphanumeric: String,
+            pub localization_value: LocalizationValue,
+            pub comment: String,
+            /// `shouldTranslate` of the catalog, `false` means only the source is relevant
+            pub should_translate: bool,
+            /// Per language the substitutions of the value by name
+            pub substitutions: BTreeMap<String, BTreeMap<String, Substitution>>,
+        }
+
+        /// A named argument like `%#@count@`, see [crate::types::input::Substitution]
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct Substitution {
+            pub arg_num: usize,
+            /// The specifier without `%`, like `lld`
+            pub format_specifier: String,
+            pub plural: Vec<SinglePluralVariation>,
+        }
+
+        impl Substitution {
+            /// The positional specifier of the argument, like `%1$lld`
+            pub fn specifier(&self) -> String {
+                format!("%{}${}", self.arg_num, self.format_specifier)
+            }
+        }
+
+        impl SingleTranslation {
+            pub fn translation_for(&self, language: &str) -> Option<&Translation> {
+                self.localization_value.language_translation.get(language)
+            }
+
+            /// Per language the plural values in canonical order. Every language gets the union of
+            /// the cases used by any language, missing cases are filled with an empty string.
+            /// Languages with a plain translation are skipped.
+            pub fn plural_matrix(&self) -> BTreeMap<String, BTreeMap<PluralVariate, String>> {
+                let plurals: Vec<_> = self
+                    .localization_value
+                    .language_translation
+                    .iter()
+                    .filter_map(|(language, translation)| match translation {
+                        Translation::PluralVariation(pv) => Some((language, pv)),
+                        Translation::Localization(_) | Translation::DeviceVariation(_) => None,
+                    })
+                    .collect();
+                let variates: BTreeSet<_> = plurals
+                    .iter()
+                    .flat_map(|(_, pv)| pv.iter().map(|single| single.variate.clone()))
+                    .collect();
+
+                plurals
+                    .into_iter()
+                    .map(|(language, pv)| {
+                        let cases = variates
+                            .iter()
+                            .map(|variate| {
+                                let value = pv
+                                    .iter()
+                                    .find(|single| &single.variate == variate)
+                                    .map(|single| single.translation_value.value.to_string())
+                                    .unwrap_or_default();
+
+                                (variate.clone(), value)
+                            })
+                            .collect();
+
+                        (language.to_string(), cases)
+                    })
+                    .collect()
+            }
+
+            /// The number of arguments a generated function needs: the highest argument position
+            /// across every language and plural case
+            pub fn max_argument_index(&self) -> usize {
+                self.localization_value
+                    .language_translation
+                    .values()
+                    .flat_map(crate::validate::translation_positions)
+                    .max()
+                    .unwrap_or_default()
+            }
+
+            /// True when the Android resource name differs from the raw key
+            pub fn is_key_transformed(&self) -> bool {
+                self.key_raw != self.key_alphanumeric
+            }
+        }
+
+        #[derive(Debug, Clone, Default)]
+        pub struct LocalizedPerLanguageInfo {
+            pub word_count: usize,
+            pub translations: Vec<SingleLocalizedPerLanguage>,
+        }
+
+        #[derive(Debug, Clone)]
+        pub struct SingleLocalizedPerLanguage {
+            pub key_raw: String,
+            pub key_alphanumeric: String,
+            pub translation: Translation,
+            pub comment: String,
+            pub should_translate: bool,
+            pub substitutions: BTreeMap<String, Substitution>,
+        }
+
+        #[derive(Debug, Clone)]
+        pub struct LocalizedPerLanguage {
+            pub source_language: String,
+            pub language_localized: BTreeMap<String, LocalizedPerLanguageInfo>,
+        }
+
+        impl LocalizedPerLanguage {
+            /// The characters of every value of `language`, plural cases summed. For CJK
+            /// languages this is more meaningful than the word count.
+            pub fn character_count(&self, language: &str) -> usize {
+                self.language_localized
+                    .get(language)
+                    .map(|info| {
+                        info.translations
+                            .iter()
+                            .flat_map(|t| t.translation.translation_values())
+                            .map(|tv| tv.value.chars().count())
+                            .sum()
+                    })
+                    .unwrap_or_default()
+            }
+
+            /// The percentage of the source keys which are fully translated in `language`, keys
+            /// missing from the source don't count
+            pub fn completion_percent(&self, language: &str) -> f64 {
+                let source_keys: alloc::collections::BTreeSet<_> = self
+                    .language_localized
+                    .get(&self.source_language)
+                    .into_iter()
+                    .flat_map(|info| &info.translations)
+                    .map(|t| t.key_raw.as_str())
+                    .collect();
+
+                if source_keys.is_empty() {
+                    return 100.0;
+                }
+
+                let translated = self
+                    .language_localized
+                    .get(language)
+                    .map(|info| {
+                        info.translations
+                            .iter()
+                            .filter(|t| {
+                                source_keys.contains(t.key_raw.as_str())
+                                    && t.translation.is_translated()
+                            })
+                            .count()
+                    })
+                    .unwrap_or_default();
+
+                translated as f64 / source_keys.len() as f64 * 100.0
+            }
+
+            /// Every language with its [Self::completion_percent], the most complete first
+            pub fn languages_by_completion(&self) -> Vec<(String, f32)> {
+                let mut languages: Vec<_> = self
+                    .language_localized
+                    .keys()
+                    .map(|language| {
+                        (
+                            language.to_string(),
+                            self.completion_percent(language) as f32,
+                        )
+                    })
+                    .collect();
+
+                languages.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+                languages
+            }
+        }
+
+        impl Localizable {
+            pub fn localized_per_language(&self) -> LocalizedPerLanguage {
+                let mut localized_per_language = LocalizedPerLanguage {
+                    source_language: self.source_language.to_string(),
+                    language_localized: Default::default(),
+                };
+
+                for single_translation in &self.single_translation {
+                    for (language, translation) in
+                        &single_translation.localization_value.language_translation
+                    {
+                        let single_localized_per_language = localized_per_language
+                            .language_localized
+                            .entry(language.to_string())
+                            .or_default();
+
+                        single_localized_per_language.translations.push(
+                            SingleLocalizedPerLanguage {
+                                key_raw: single_translation.key_raw.to_string(),
+                                key_alphanumeric: single_translation.key_alphanumeric.to_string(),
+                                translation: translation.clone(),
+                                comment: single_translation.comment.to_string(),
+                                should_translate: single_translation.should_translate,
+                                substitutions: single_translation
+                                    .substitutions
+                                    .get(language)
+                                    .cloned()
+                                    .unwrap_or_default(),
+                            },
+                        );
+
+                        single_localized_per_language.word_count += translation.word_count();
+                    }
+                }
+
+                for (language, localized) in &localized_per_language.language_localized {
+                    log::debug!(
+                        "Language: {} word count: {}",
+                        language,
+                        localized.word_count
+                    )
+                }
+
+                localized_per_language
+            }
+
+            /// The translations per language of a single key
+            pub fn translations_for_key(&self, key_raw: &str) -> Option<&LocalizationValue> {
+                self.single_translation
+                    .iter()
+                    .find(|st| st.key_raw == key_raw)
+                    .map(|st| &st.localization_value)
+            }
+
+            /// Raw key → source value, plurals are rendered as their `other` case and device
+            /// variations as [Translation::device_fallback]
+            pub fn source_flat_map(&self) -> BTreeMap<String, String> {
+                self.single_translation
+                    .iter()
+                    .filter_map(|st| {
+                        let value = match st.translation_for(&self.source_language)? {
+                            Translation::Localization(l) => l.value.to_string(),
+                            Translation::PluralVariation(pv) => pv
+                                .iter()
+                                .find(|single| single.variate == PluralVariate::Other)?
+                                .translation_value
+                                .value
+                                .to_string(),
+                            device @ Translation::DeviceVariation(_) => {
+                                device.device_fallback()?.value.to_string()
+                            }
+                        };
+
+                        Some((st.key_raw.to_string(), value))
+                    })
+                    .collect()
+            }
+
+            /// Builds a [LocalizableIndex] for repeated lookups by raw key, which are otherwise a
+            /// linear scan like [Localizable::translations_for_key]. Every call scans all keys, so
+            /// build it once and keep it while querying. It borrows the catalog, so it can't go
+            /// stale.
+            pub fn build_index(&self) -> LocalizableIndex<'_> {
+                LocalizableIndex {
+                    by_key: self
+                        .single_translation
+                        .iter()
+                        .map(|st| (st.key_raw.as_str(), st))
+                        .collect(),
+                }
+            }
+
+            /// Every language used by at least one key, including the source language
+            pub fn languages(&self) -> BTreeSet<String> {
+                self.single_translation
+                    .iter()
+                    .flat_map(|st| st.localization_value.language_translation.keys().cloned())
+                    .chain([self.source_language.to_string()])
+                    .collect()
+            }
+
+            /// Every key with the word count of its source value, most words first. Keys with
+            /// the same count stay in key order.
+            pub fn keys_by_word_count(&self) -> Vec<(String, usize)> {
+                let mut keys: Vec<_> = self
+                    .single_translation
+                    .iter()
+                    .map(|st| {
+                        (
+                            st.key_raw.to_string(),
+                            st.translation_for(&self.source_language)
+                                .map(Translation::word_count)
+                                .unwrap_or_default(),
+                        )
+                    })
+                    .collect();
+
+                keys.sort_by_key(|(_, word_count)| core::cmp::Reverse(*word_count));
+
+                keys
+            }
+
+            /// Returns a copy only containing the keys starting with `prefix`
+            pub fn filter_by_prefix(&self, prefix: &str) -> Localizable {
+                Localizable {
+                    source_language: self.source_language.to_string(),
+                    single_translation: self
+                        .single_translation
+                        .iter()
+                        .filter(|st| st.key_raw.starts_with(prefix))
+                        .cloned()
+                        .collect(),
+                    version: self.version.to_string(),
+                }
+            }
+
+            /// Maps a format argument count to the number of keys whose source value takes that
+            /// many arguments. For plurals the arguments of every case are combined.
+            pub fn argument_arity_histogram(&self) -> BTreeMap<usize, usize> {
+                let mut histogram = BTreeMap::new();
+
+                for single_translation in &self.single_translation {
+                    let arity = single_translation
+                        .translation_for(&self.source_language)
+                        .map(|source| crate::validate::translation_positions(source).len())
+                        .unwrap_or_default();
+
+                    *histogram.entry(arity).or_default() += 1;
+                }
+
+                histogram
+            }
+
+            /// See [LocalizedPerLanguage::completion_percent]
+            pub fn completion_percent(&self, language: &str) -> f64 {
+                self.localized_per_language().completion_percent(language)
+            }
+
+            pub fn stats_for_prefix(&self, prefix: &str) -> PrefixStats {
+                let filtered = self.filter_by_prefix(prefix);
+
+                PrefixStats {
+                    key_count: filtered.single_translation.len(),
+                    word_count_per_language: filtered
+                        .localized_per_language()
+                        .language_localized
+                        .into_iter()
+                        .map(|(language, info)| (language, info.word_count))
+                        .collect(),
+                }
+            }
+
+            /// Sets the state of every value in `from` state to `to`, plural cases are handled one
+            /// by one. An empty `languages` slice means every language. Returns the changed count.
+            pub fn transition_states(
+                &mut self,
+                from: State,
+                to: State,
+                languages: &[String],
+            ) -> usize {
+                let mut changed = 0;
+
+                for single_translation in &mut self.single_translation {
+                    for (language, translation) in
+                        &mut single_translation.localization_value.language_translation
+                    {
+                        if !languages.is_empty() && !languages.contains(language) {
+                            continue;
+                        }
+
+                        let values: Vec<&mut TranslationValue> = match translation {
+                            Translation::Localization(l) => vec![l],
+                            Translation::PluralVariation(pv) => pv
+                                .iter_mut()
+                                .map(|single| &mut single.translation_value)
+                                .collect(),
+                            Translation::DeviceVariation(dv) => dv
+                                .iter_mut()
+                                .map(|single| &mut single.translation_value)
+                                .collect(),
+                        };
+
+                        for value in values {
+                            if value.state == from.apple_name() {
+                                value.state = to.apple_name().to_string();
+                                changed += 1;
+                            }
+                        }
+                    }
+                }
+
+                changed
+            }
+
+            /// Changes the language code `from` to `to` in every entry, e.g. to fix `iw` to `he`.
+            /// The source language is renamed as well when it's `from`.
+            pub fn rename_language(
+                &mut self,
+                from: &str,
+                to: &str,
+            ) -> Result<(), RenameLanguageError> {
+                let languages = self.languages();
+
+                if !languages.contains(from) {
+                    return Err(RenameLanguageError::UnknownLanguage(from.to_string()));
+                }
+
+                if languages.contains(to) {
+                    return Err(RenameLanguageError::LanguageExists(to.to_string()));
+                }
+
+                for single_translation in &mut self.single_translation {
+                    let language_translation =
+                        &mut single_translation.localization_value.language_translation;
+
+                    if let Some(translation) = language_translation.remove(from) {
+                        language_translation.insert(to.to_string(), translation);
+                    }
+                }
+
+                if self.source_language == from {
+                    self.source_language = to.to_string();
+                }
+
+                Ok(())
+            }
+
+            /// Replaces the comment of the key, returns false when the key doesn't exist
+            pub fn set_comment(&mut self, key_raw: &str, comment: &str) -> bool {
+                match self
+                    .single_translation
+                    .iter_mut()
+                    .find(|st| st.key_raw == key_raw)
+                {
+                    Some(single_translation) => {
+                        single_translation.comment = comment.to_string();
+
+                        true
+                    }
+                    None => false,
+                }
+            }
+
+            /// Like [Localizable::set_comment], but the comment is marked as machine-generated
+            /// with `prefix`, e.g. [crate::DEFAULT_AUTO_COMMENT_PREFIX]. Use
+            /// [ParseConfig::strip_comment_prefix] to remove the marker on re-parse.
+            pub fn set_generated_comment(
+                &mut self,
+                key_raw: &str,
+                comment: &str,
+                prefix: &str,
+            ) -> bool {
+                self.set_comment(key_raw, &format!("{prefix} {comment}"))
+            }
+
+            /// Inserts a key with only a `translated` source value, keeping the keys sorted
+            pub fn add_key(
+                &mut self,
+                key_raw: &str,
+                source_value: &str,
+                comment: &str,
+            ) -> Result<(), AddKeyError> {
+                if key_raw.is_empty() || key_raw.trim() != key_raw {
+                    return Err(AddKeyError::InvalidKey(key_raw.to_string()));
+                }
+
+                let key_alphanumeric = crate::android::resource_name(key_raw);
+
+                if let Some(existing) = self
+                    .single_translation
+                    .iter()
+                    .find(|st| st.key_raw == key_raw || st.key_alphanumeric == key_alphanumeric)
+                {
+                    return Err(if existing.key_raw == key_raw {
+                        AddKeyError::KeyExists(key_raw.to_string())
+                    } else {
+                        AddKeyError::AndroidNameCollision {
+                            key_raw: key_raw.to_string(),
+                            existing_key_raw: existing.key_raw.to_string(),
+                        }
+                    });
+                }
+
+                let index = self
+                    .single_translation
+                    .partition_point(|st| st.key_raw.as_str() < key_raw);
+
+                self.single_translation.insert(
+                    index,
+                    SingleTranslation {
+                        key_raw: key_raw.to_string(),
+                        key_alphanumeric,
+                        localization_value: LocalizationValue {
+                            language_translation: BTreeMap::from([(
+                                self.source_language.to_string(),
+                                Translation::Localization(TranslationValue {
+                                    state: crate::TRANSLATED_STATE.to_string(),
+                                    value: source_value.to_string(),
+                                }),
+                            )]),
+                        },
+                        comment: comment.to_string(),
+                        should_translate: true,
+                        substitutions: BTreeMap::new(),
+                    },
+                );
+
+                Ok(())
+            }
+
+            /// Seeds `language` from `glossary`, which maps source values to pre-approved
+            /// translations. Only simple strings which are untranslated in `language` (missing,
+            /// empty or still `new`, like the source copy Xcode adds) are filled, with the
+            /// `needs_review` state. Returns the seeded keys.
+            pub fn seed_from_glossary(
+                &mut self,
+                language: &str,
+                glossary: &BTreeMap<String, String>,
+            ) -> Vec<String> {
+                let mut seeded = vec![];
+
+                for single_translation in &mut self.single_translation {
+                    if !single_translation.should_translate {
+                        continue;
+                    }
+
+                    let Some(Translation::Localization(source)) =
+                        single_translation.translation_for(&self.source_language)
+                    else {
+                        continue;
+                    };
+                    let Some(approved) = glossary.get(&source.value) else {
+                        continue;
+                    };
+                    let untranslated = match single_translation.translation_for(language) {
+                        None => true,
+                        Some(Translation::Localization(l)) => {
+                            l.value.is_empty() || l.state == crate::NEW_STATE
+                        }
+                        Some(_) => false,
+                    };
+
+                    if !untranslated {
+                        continue;
+                    }
+
+                    single_translation
+                        .localization_value
+                        .language_translation
+                        .insert(
+                            language.to_string(),
+                            Translation::Localization(TranslationValue {
+                                state: crate::NEEDS_REVIEW_STATE.to_string(),
+                                value: approved.to_string(),
+                            }),
+                        );
+                    seeded.push(single_translation.key_raw.to_string());
+                }
+
+                seeded
+            }
+
+            /// Applies [normalize_language_code] to every language, e.g. `pt_br` becomes `pt-BR`.
+            /// Languages which collapse to the same code are merged. When they disagree about a
+            /// key nothing is changed and the conflicting key is returned.
+            pub fn normalize_language_codes(&mut self) -> Result<(), LanguageConflict> {
+                let mut normalized = self.clone();
+
+                normalized.source_language = normalize_language_code(&self.source_language);
+
+                for single_translation in &mut normalized.single_translation {
+                    let language_translation = core::mem::take(
+                        &mut single_translation.localization_value.language_translation,
+                    );
+
+                    for (language, translation) in language_translation {
+                        let language = normalize_language_code(&language);
+
+                        match single_translation
+                            .localization_value
+                            .language_translation
+                            .get(&language)
+                        {
+                            Some(existing) if existing != &translation => {
+                                return Err(LanguageConflict {
+                                    key_raw: single_translation.key_raw.to_string(),
+                                    language,
+                                });
+                            }
+                            Some(_) => {}
+                            None => {
+                                single_translation
+                                    .localization_value
+                                    .language_translation
+                                    .insert(language, translation);
+                            }
+                        }
+                    }
+                }
+
+                *self = normalized;
+
+                Ok(())
+            }
+
+            /// Maps every distinct comment to the raw keys using it
+            pub fn all_comments(&self) -> BTreeMap<String, Vec<String>> {
+                let mut comments: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+                for single_translation in &self.single_translation {
+                    comments
+                        .entry(single_translation.comment.to_string())
+                        .or_default()
+                        .push(single_translation.key_raw.to_string());
+                }
+
+                comments
+            }
+
+            /// The non-empty comments used by more than one key, see [Localizable::all_comments]
+            pub fn duplicate_comments(&self) -> Vec<(String, Vec<String>)> {
+                self.all_comments()
+                    .into_iter()
+                    .filter(|(comment, keys)| !comment.trim().is_empty() && keys.len() > 1)
+                    .collect()
+            }
+        }
+
+        /// The BCP-47 casing and separator: lowercase language, titlecase script (`Hans`),
+        /// uppercase region (`BR`), e.g. `ZH_hans` becomes `zh-Hans`
+        pub fn normalize_language_code(code: &str) -> String {
+            code.trim()
+                .split(['-', '_'])
+                .enumerate()
+                .map(|(index, part)| {
+                    if index == 0 {
+                        part.to_lowercase()
+                    } else if part.len() == 4 && part.chars().all(|c| c.is_ascii_alphabetic()) {
+                        format!("{}{}", part[..1].to_uppercase(), part[1..].to_lowercase())
+                    } else if part.len() == 2 && part.chars().all(|c| c.is_ascii_alphabetic()) {
+                        part.to_uppercase()
+                    } else {
+                        part.to_lowercase()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("-")
+        }
+
+        /// Two language codes normalize to the same code but have different translations
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct LanguageConflict {
+            pub key_raw: String,
+            pub language: String,
+        }
+
+        impl Display for LanguageConflict {
+            fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+                write!(
+                    f,
+                    "Conflicting translations for {} ({})",
+                    self.key_raw, self.language
+                )
+            }
+        }
+
+        impl Error for LanguageConflict {}
+
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub enum AddKeyError {
+            /// The key is empty or has surrounding whitespace
+            InvalidKey(String),
+            KeyExists(String),
+            /// The key has the same Android resource name as an existing key
+            AndroidNameCollision {
+                key_raw: String,
+                existing_key_raw: String,
+            },
+        }
+
+        impl Display for AddKeyError {
+            fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+                match self {
+                    AddKeyError::InvalidKey(key) => write!(f, "Invalid translation key: {}", key),
+                    AddKeyError::KeyExists(key) => write!(f, "Key already exists: {}", key),
+                    AddKeyError::AndroidNameCollision {
+                        key_raw,
+                        existing_key_raw,
+                    } => write!(
+                        f,
+                        "Key {} has the same Android name as {}",
+                        key_raw, existing_key_raw
+                    ),
+                }
+            }
+        }
+
+        impl Error for AddKeyError {}
+
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub enum RenameLanguageError {
+            /// No entry has the language which should be renamed
+            UnknownLanguage(String),
+            /// The new language code is already used
+            LanguageExists(String),
+        }
+
+        impl Display for RenameLanguageError {
+            fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+                match self {
+                    RenameLanguageError::UnknownLanguage(language) => {
+                        write!(f, "Unknown language: {}", language)
+                    }
+                    RenameLanguageError::LanguageExists(language) => {
+                        write!(f, "Language already exists: {}", language)
+                    }
+                }
+            }
+        }
+
+        impl Error for RenameLanguageError {}
+
+        #[derive(Debug, Clone, Default, PartialEq, Eq)]
+        pub struct PrefixStats {
+            pub key_count: usize,
+            pub word_count_per_language: BTreeMap<String, usize>,
+        }
+
+        /// An Apple-only specifier which survived the Android conversion
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct LeftoverSpecifier {
+            pub key_raw: String,
+            pub language: String,
+            pub specifier: String,
+        }
+
+        impl LocalizedPerLanguage {
+            /// Scans the values as they are emitted for Android for specifiers only Apple understands
+            pub fn leftover_ios_specifiers(&self) -> Vec<LeftoverSpecifier> {
+                let mut leftovers = vec![];
+
+                for (language, info) in &self.language_localized {
+                    for translation in &info.translations {
+                        let values: Vec<String> = match &translation.translation {
+                            Translation::PluralVariation(pv) => pv
+                                .iter()
+                                .map(|single| single.translation_value.sanitize_for_android())
+                                .collect(),
+                            simple => simple
+                                .simple_value()
+                                .map(|tv| {
+                                    crate::types::output::sanitize_for_android(
+                                        &crate::specifier::resolve_substitutions(
+                                            &tv.value,
+                                            &translation.substitutions,
+                                        ),
+                                    )
+                                })
+                                .into_iter()
+                                .collect(),
+                        };
+
+                        for value in values {
+                            for specifier in crate::specifier::android_leftovers(&value) {
+                                leftovers.push(LeftoverSpecifier {
+                                    key_raw: translation.key_raw.to_string(),
+                                    language: language.to_string(),
+                                    specifier,
+                                });
+                            }
+                        }
+                    }
+                }
+
+                leftovers
+            }
+        }
+
+        #[cfg(feature = "std")]
+        #[derive(Debug, Clone, Default)]
+        pub struct AndroidWriteConfig {
+            pub write_in: PathBuf,
+            pub only_write_language_code: Option<String>,
+            pub directory_casing: DirectoryCasing,
+            /// Names the written file after the string table, e.g. [Parsed::table_name]
+            /// `Localizable` writes `localizable.xml` instead of `strings.xml`
+            pub table_name: Option<String>,
+            /// The name of the written file, e.g. `strings_generated.xml`. Takes precedence over
+            /// [AndroidWriteConfig::table_name], defaults to `strings.xml`. Must end in `.xml` and
+            /// can't contain a path, like `../` or `sub/`.
+            pub file_name: Option<String>,
+            /// Writes a note with generation details next to the resources in every directory
+            pub companion_file: Option<CompanionFile>,
+            /// Writes up to this many languages at the same time on a rayon thread pool, `None`
+            /// writes them one by one. Keeps the number of open files bounded for catalogs with
+            /// many languages.
+            #[cfg(feature = "rayon")]
+            pub max_concurrency: Option<usize>,
+        }
+
+        #[cfg(feature = "std")]
+        #[derive(Debug, Clone, Default)]
+        pub struct CompanionFile {
+            /// E.g. `README.txt`
+            pub file_name: String,
+            /// The catalog the resources are generated from, recorded in the note
+            pub source_file: String,
+        }
+
+        #[cfg(feature = "std")]
+        impl CompanionFile {
+            pub fn content(&self, language: &str, completion_percent: f64) -> String {
+                let generated_at = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or_default();
+
+                format!(
+                    "Generated by {}, do not edit\nGenerated at (unix time): {generated_at}\nSource file: {}\nLanguage: {language}\nCompletion: {completion_percent:.1}%\n",
+                    env!("CARGO_PKG_NAME"),
+                    self.source_file
+                )
+            }
+        }
+
+        #[cfg(feature = "std")]
+        impl AndroidWriteConfig {
+            pub fn file_name(&self) -> Result<String, ParsedError> {
+                if let Some(file_name) = &self.file_name {
+                    if !file_name.ends_with(".xml")
+                        || file_name.len() == ".xml".len()
+                        || file_name.contains(['/', '\\'])
+                        || file_name.contains("..")
+                    {
+                        return Err(ParsedError::InvalidFileName(file_name.to_string()));
+                    }
+
+                    return Ok(file_name.to_string());
+                }
+
+                Ok(match &self.table_name {
+                    Some(table_name) => {
+                        format!("{}.xml", crate::android::resource_name(table_name))
+                    }
+                    None => "strings.xml".to_string(),
+                })
+            }
+        }
+
+        /// Controls how the language code is written in the `values-xx` directory name
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+        #[serde(rename_all = "snake_case")]
+        pub enum DirectoryCasing {
+            /// Lowercase language, uppercase region prefixed with `r`, e.g. `values-pt-rBR`
+            #[default]
+            Android,
+            /// The language code exactly as it appears in the xcstrings file, e.g. `values-pt-BR`
+            Verbatim,
+        }
+
+        impl DirectoryCasing {
+            /// `values` for the source language, else `values-` with the [Self::language_qualifier]
+            pub fn values_directory(&self, language: &str, source_language: &str) -> String {
+                if language == source_language {
+                    "values".to_string()
+                } else {
+                    format!("values-{}", self.language_qualifier(language))
+                }
+            }
+
+            pub fn language_qualifier(&self, language: &str) -> String {
+                match self {
+                    DirectoryCasing::Verbatim => language.to_string(),
+                    DirectoryCasing::Android => {
+                        let mut parts = language.split(['-', '_']);
+                        let language_code = parts.next().unwrap_or_default().to_lowercase();
+                        let subtags: Vec<_> = parts.collect();
+                        let is_script = |part: &&str| {
+                            part.len() == 4 && part.chars().all(|c| c.is_ascii_alphabetic())
+                        };
+                        let is_region = |part: &str| {
+                            (part.len() == 2 && part.chars().all(|c| c.is_ascii_alphabetic()))
+                                || (part.len() == 3 && part.chars().all(|c| c.is_ascii_digit()))
+                        };
+
+                        // Android can only express scripts in the BCP-47 form, e.g. `b+zh+Hans`
+                        if subtags.iter().any(is_script) {
+                            let mut qualifier = format!("b+{language_code}");
+
+                            for part in &subtags {
+                                qualifier.push('+');
+
+                                if is_script(part) {
+                                    qualifier.push_str(&part[..1].to_uppercase());
+                                    qualifier.push_str(&part[1..].to_lowercase());
+                                } else if is_region(part) {
+                                    qualifier.push_str(&part.to_uppercase());
+                                } else {
+                                    qualifier.push_str(part);
+                                }
+                            }
+
+                            return qualifier;
+                        }
+
+                        let mut qualifier = language_code;
+
+                        for part in subtags {
+                            qualifier.push('-');
+
+                            if is_region(part) {
+                                qualifier.push('r');
+                                qualifier.push_str(&part.to_uppercase());
+                            } else {
+                                qualifier.push_str(part);
+                            }
+                        }
+
+                        qualifier
+                    }
+                }
+            }
+        }
+
+        #[derive(Debug, Clone, Default)]
+        pub struct AndroidLocalizeConfig {
+            pub app_name: String,
+            /// Maps a key prefix to a file base name, e.g. `onboarding.` to `strings_onboarding`.
+            /// Matching keys are emitted in that file instead of `strings.xml`, the longest prefix wins.
+            /// `strings.xml` is always written, also when it's left empty (`<resources>` only).
+            pub file_groups: BTreeMap<String, String>,
+            /// Prepends `<?xml version="1.0" encoding="utf-8"?>` to every file
+            pub xml_declaration: bool,
+            /// Written as an XML comment above `<resources>` in every file, e.g. `DO NOT EDIT`
+            pub header_comment: Option<String>,
+            pub whitespace: WhitespaceHandling,
+            /// Adds `tools:locale` to the `<resources>` element for the Android Studio preview, with
+            /// the Android qualifier of the language (see [DirectoryCasing::language_qualifier])
+            pub tools_locale: bool,
+            /// Wraps values containing markup (a `<`) in `<![CDATA[...]]>` instead of writing the
+            /// markup as is, which Android parses as styling
+            pub cdata_markup: bool,
+            /// The order of the `<item>`s in every `<plurals>`
+            pub plural_order: PluralOrder,
+            /// Emits the `other` value for categories the language requires (see
+            /// [PluralVariate::required_for]) but the plural doesn't have
+            pub fill_missing_plural_from_other: bool,
+            /// Only emits the source language, which is written to the bare `values` directory
+            pub source_language_only: bool,
+            /// Fails with [ParsedError::PlaceholderMismatch] instead of emitting translations
+            /// whose placeholders don't match the source, these crash at runtime
+            pub strict_placeholders: bool,
+            /// Emits keys with `shouldTranslate: false` with `translatable="false"`, only for the
+            /// source language. By default these keys are skipped.
+            pub emit_non_translatable: bool,
+            /// A file base name like `donottranslate`. Keys with `shouldTranslate: false` are
+            /// emitted in that file with `translatable="false"`, only for the source language.
+            /// Implies [AndroidLocalizeConfig::emit_non_translatable].
+            pub non_translatable_file: Option<String>,
+            /// Emits `@string/first_key` for a simple string whose value equals an earlier simple
+            /// string of the same language, instead of repeating the value. Plurals are never
+            /// deduplicated.
+            pub dedupe_references: bool,
+            #[cfg(feature = "std")]
+            pub write_config: Option<AndroidWriteConfig>,
+        }
+
+        impl AndroidLocalizeConfig {
+            /// [sanitize_for_android] with the value options of this config applied
+            pub fn sanitize(&self, value: &TranslationValue) -> String {
+                if self.cdata_markup && value.value.contains('<') {
+                    return cdata_for_android(&value.value, self.whitespace);
+                }
+
+                handle_whitespace(&value.sanitize_for_android(), self.whitespace)
+            }
+
+            /// Wraps the elements in a `<resources>` element
+            fn resources(&self, language: &str, xml: &[String]) -> String {
+                let declaration = if self.xml_declaration {
+                    "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n"
+                } else {
+                    ""
+                };
+                let attributes = if self.tools_locale {
+                    format!(
+                        " xmlns:tools=\"http://schemas.android.com/tools\" tools:locale=\"{}\"",
+                        DirectoryCasing::Android.language_qualifier(language)
+                    )
+                } else {
+                    "".to_string()
+                };
+
+                let header = match &self.header_comment {
+                    Some(comment) => format!("<!-- {} -->\n", escape_xml_comment(comment)),
+                    None => "".to_string(),
+                };
+
+                format!(
+                    "{declaration}{header}<resources{attributes}>\n{}\n</resources>",
+                    xml.join("\n")
+                )
+            }
+
+            /// The [AndroidLocalizeConfig::app_name] as Android value, unlike translations the
+            /// XML special characters are escaped
+            pub fn sanitized_app_name(&self) -> String {
+                sanitize_for_android(&escape_xml(&self.app_name))
+            }
+
+            fn file_group(&self, key_raw: &str) -> Option<&String> {
+                self.file_groups
+                    .iter()
+                    .filter(|(prefix, _)| key_raw.starts_with(prefix.as_str()))
+                    .max_by_key(|(prefix, _)| prefix.len())
+                    .map(|(_, file)| file)
+            }
+        }
+
+        #[derive(Debug, Clone, Serialize)]
+        pub struct WrittenXml {
+            pub language_code: String,
+            pub sub_dir: String,
+            pub file_names: Vec<String>,
+        }
+
+        #[derive(Debug, Clone, Default)]
+        pub struct LocalizedForAndroid {
+            /// The content of `strings.xml` per language, for every written language even if all
+            /// its keys are in [LocalizedForAndroid::grouped_languages]
+            pub sorted_languages: BTreeMap<String, String>,
+            /// Per language the additional files by file name, see [AndroidLocalizeConfig::file_groups]
+            pub grouped_languages: BTreeMap<String, BTreeMap<String, String>>,
+            pub written_xmls: Vec<WrittenXml>,
+        }
+
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum SkipReason {
+            /// The key has `shouldTranslate: false`. It's skipped for every language, the source is
+            /// only written when [AndroidLocalizeConfig::emit_non_translatable] is set.
+            NonTranslatable,
+            /// The translation is still `new`, Android falls back to the source
+            NewState,
+        }
+
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct SkippedEntry {
+            pub key_raw: String,
+            pub language: String,
+            pub reason: SkipReason,
+        }
+
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub enum WarningKind {
+            /// The argument positions differ from the source, see
+            /// [crate::validate::PositionIssue]
+            PositionMismatch {
+                expected: BTreeSet<usize>,
+                actual: BTreeSet<usize>,
+            },
+            /// The translation is a plural while the source is a string, or the other way around
+            MixedKind,
+            /// A specifier only Apple understands is left after the conversion, like `%@`
+            AppleOnlySpecifier(String),
+        }
+
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct ConversionWarning {
+            pub key_raw: String,
+            pub language: String,
+            pub kind: WarningKind,
+        }
+
+        /// Everything non-fatal about a conversion, see
+        /// [Localizable::localized_for_android_with_report]
+        #[derive(Debug, Clone, Default)]
+        pub struct ConversionReport {
+            pub languages_written: Vec<String>,
+            pub skipped: Vec<SkippedEntry>,
+            /// The validation warnings, e.g. placeholder mismatches
+            pub warnings: Vec<ConversionWarning>,
+        }
+
+        impl Localizable {
+            /// Like [LocalizedPerLanguage::localized_for_android], but `new` translations are
+            /// skipped as well. What was skipped and the validation warnings are collected in the
+            /// [ConversionReport].
+            pub fn localized_for_android_with_report(
+                &self,
+                config: AndroidLocalizeConfig,
+            ) -> Result<(LocalizedForAndroid, ConversionReport), ParsedError> {
+                let mut report = ConversionReport::default();
+                let mut localizable = self.clone();
+                let emit_non_translatable =
+                    config.emit_non_translatable || config.non_translatable_file.is_some();
+
+                for single_translation in &mut localizable.single_translation {
+                    let key_raw = &single_translation.key_raw;
+                    let should_translate = single_translation.should_translate;
+
+                    single_translation
+                        .localization_value
+                        .language_translation
+                        .retain(|language, translation| {
+                            let is_source = language == &self.source_language;
+
+                            if is_source && (should_translate || emit_non_translatable) {
+                                return true;
+                            }
+
+                            let reason = if !should_translate {
+                                SkipReason::NonTranslatable
+                            } else if translation
+                                .translation_values()
+                                .iter()
+                                .any(|tv| tv.state == crate::NEW_STATE)
+                            {
+                                SkipReason::NewState
+                            } else {
+                                return true;
+                            };
+
+                            report.skipped.push(SkippedEntry {
+                                key_raw: key_raw.to_string(),
+                                language: language.to_string(),
+                                reason,
+                            });
+
+                            false
+                        });
+                }
+
+                for issue in localizable.positional_specifier_issues() {
+                    report.warnings.push(ConversionWarning {
+                        key_raw: issue.key_raw,
+                        language: issue.language,
+                        kind: WarningKind::PositionMismatch {
+                            expected: issue.expected,
+                            actual: issue.actual,
+                        },
+                    });
+                }
+
+                for issue in localizable.mixed_translation_kinds() {
+                    let source_kind = issue.kinds.get(&self.source_language);
+
+                    for (language, kind) in &issue.kinds {
+                        if Some(kind) != source_kind {
+                            report.warnings.push(ConversionWarning {
+                                key_raw: issue.key_raw.to_string(),
+                                language: language.to_string(),
+                                kind: WarningKind::MixedKind,
+                            });
+                        }
+                    }
+                }
+
+                let localized_per_language = localizable.localized_per_language();
+
+                for leftover in localized_per_language.leftover_ios_specifiers() {
+                    report.warnings.push(ConversionWarning {
+                        key_raw: leftover.key_raw,
+                        language: leftover.language,
+                        kind: WarningKind::AppleOnlySpecifier(leftover.specifier),
+                    });
+                }
+
+                let localized = localized_per_language.localized_for_android(config)?;
+
+                report.languages_written = localized.sorted_languages.keys().cloned().collect();
+
+                Ok((localized, report))
+            }
+        }
+
+        impl LocalizedPerLanguage {
+            pub fn localized_for_android(
+                &self,
+                config: AndroidLocalizeConfig,
+            ) -> Result<LocalizedForAndroid, ParsedError> {
+                let mut localized_for_android: LocalizedForAndroid = Default::default();
+
+                if config.strict_placeholders {
+                    let issues = self.positional_specifier_issues();
+
+                    if !issues.is_empty() {
+                        return Err(ParsedError::PlaceholderMismatch(issues));
+                    }
+                }
+
+                for (language, translations) in &self.language_localized {
+                    if config.source_language_only && language != &self.source_language {
+                        continue;
+                    }
+
+                    let mut xml = vec![];
+                    let mut grouped: BTreeMap<String, Vec<String>> = BTreeMap::new();
+                    let mut first_key_by_value: BTreeMap<String, String> = BTreeMap::new();
+                    let ordered = translations.clone();
+
+                    for translation in ordered.translations {
+                        let emit_non_translatable =
+                            config.emit_non_translatable || config.non_translatable_file.is_some();
+
+                        if !translation.should_translate
+                            && (!emit_non_translatable || language != &self.source_language)
+                        {
+                            continue;
+                        }
+
+                        let non_translatable_file = config
+                            .non_translatable_file
+                            .as_ref()
+                            .filter(|_| !translation.should_translate);
+                        let translatable = if translation.should_translate {
+                            ""
+                        } else {
+                            " translatable=\"false\""
+                        };
+                        let content = match &translation.translation {
+                            Translation::Localization(_) | Translation::DeviceVariation(_) => {
+                                let mut localization = translation
+                                    .translation
+                                    .simple_value()
+                                    .cloned()
+                                    .unwrap_or_default();
+
+                                localization.value = crate::specifier::resolve_substitutions(
+                                    &localization.value,
+                                    &translation.substitutions,
+                                );
+                                let mut value = config.sanitize(&localization);
+
+                                if config.dedupe_references && !value.is_empty() {
+                                    match first_key_by_value.get(&value) {
+                                        Some(first_key) => value = format!("@string/{first_key}"),
+                                        None => {
+                                            first_key_by_value.insert(
+                                                value.to_string(),
+                                                translation.key_alphanumeric.to_string(),
+                                            );
+                                        }
+                                    }
+                                }
+
+                                format!(
+                                    "<string name=\"{}\"{translatable}>{value}</string>",
+                                    translation.key_alphanumeric,
+                                )
+                            }
+                            Translation::PluralVariation(plural) => {
+                                if let Some(variate) =
+                                    crate::validate::duplicate_plural_cases(plural).first()
+                                {
+                                    return Err(ParsedError::DuplicatePluralCase(
+                                        crate::validate::DuplicatePluralCase {
+                                            key_raw: translation.key_raw.to_string(),
+                                            language: language.to_string(),
+                                            variate: variate.clone(),
+                                        },
+                                    ));
+                                }
+
+                                let mut temp = vec![format!(
+                                    "<plurals name=\"{}\"{translatable}>",
+                                    translation.key_alphanumeric
+                                )];
+                                let mut plural = plural.clone();
+
+                                if config.fill_missing_plural_from_other {
+                                    fill_missing_plural_from_other(&mut plural, language);
+                                }
+
+                                config.plural_order.sort(&mut plural);
+
+                                for single_plural in &plural {
+                                    temp.push(format!(
+                                        "<item quantity=\"{}\">{}</item>",
+                                        single_plural.variate.android_key(),
+                                        config.sanitize(&single_plural.translation_value)
+                                    ));
+                                }
+
+                                temp.push("</plurals>".to_string());
+
+                                temp.join("\n")
+                            }
+                        };
+
+                        match non_translatable_file
+                            .or_else(|| config.file_group(&translation.key_raw))
+                        {
+                            Some(file) => grouped
+                                .entry(format!("{file}.xml"))
+                                .or_default()
+                                .push(content),
+                            None => xml.push(content),
+                        }
+                    }
+
+                    if !grouped.is_empty() {
+                        localized_for_android.grouped_languages.insert(
+                            language.to_string(),
+                            grouped
+                                .into_iter()
+                                .map(|(file, xml)| (file, config.resources(language, &xml)))
+                                .collect(),
+                        );
+                    }
+
+                    if !config.app_name.is_empty() {
+                        xml.insert(
+                            0,
+                            format!(
+                                "<string name=\"app_name\">{}</string>",
+                                config.sanitized_app_name()
+                            ),
+                        );
+                    }
+
+                    localized_for_android
+                        .sorted_languages
+                        .insert(language.to_string(), config.resources(language, &xml));
+                }
+
+                #[cfg(feature = "std")]
+                if let Some(write_config) = config.write_config {
+                    localized_for_android.written_xmls =
+                        self.write_android_xmls(&write_config, &localized_for_android)?;
+                }
+
+                Ok(localized_for_android)
+            }
+
+            #[cfg(feature = "std")]
+            fn write_android_xmls(
+                &self,
+                write_config: &AndroidWriteConfig,
+                localized_for_android: &LocalizedForAndroid,
+            ) -> Result<Vec<WrittenXml>, ParsedError> {
+                let file_name = write_config.file_name()?;
+                let languages: Vec<_> = localized_for_android
+                    .sorted_languages
+                    .iter()
+                    .filter(|(language, _)| {
+                        write_config
+                            .only_write_language_code
+                            .as_ref()
+                            .map_or(true, |lan| lan == *language)
+                    })
+                    .collect();
+                let write = |(language, content): &(&String, &String)| {
+                    self.write_android_language(
+                        write_config,
+                        &file_name,
+                        localized_for_android,
+                        language,
+                        content,
+                    )
+                };
+
+                #[cfg(feature = "rayon")]
+                if let Some(max_concurrency) = write_config.max_concurrency {
+                    use rayon::prelude::*;
+
+                    let pool = rayon::ThreadPoolBuilder::new()
+                        .num_threads(max_concurrency.max(1))
+                        .build()
+                        .map_err(|e| ParsedError::Io(e.to_string()))?;
+
+                    // Collecting keeps the order of languages, so written_xmls stays sorted
+                    return pool.install(|| languages.par_iter().map(write).collect());
+                }
+
+                languages.iter().map(write).collect()
+            }
+
+            #[cfg(feature = "std")]
+            fn write_android_language(
+                &self,
+                write_config: &AndroidWriteConfig,
+                file_name: &str,
+                localized_for_android: &LocalizedForAndroid,
+                language: &str,
+                content: &str,
+            ) -> Result<WrittenXml, ParsedError> {
+                let sub_dir_name = write_config
+                    .directory_casing
+                    .values_directory(language, &self.source_language);
+                let sub_dir = write_config.write_in.join(&sub_dir_name);
+
+                if !sub_dir.exists() {
+                    std::fs::create_dir(&sub_dir)?;
+                }
+
+                std::fs::write(sub_dir.join(file_name), content)?;
+
+                let mut file_names = vec![file_name.to_string()];
+
+                if let Some(grouped) = localized_for_android.grouped_languages.get(language) {
+                    for (file_name, content) in grouped {
+                        std::fs::write(sub_dir.join(file_name), content)?;
+
+                        file_names.push(file_name.to_string());
+                    }
+                }
+
+                if let Some(companion_file) = &write_config.companion_file {
+                    std::fs::write(
+                        sub_dir.join(&companion_file.file_name),
+                        companion_file.content(language, self.completion_percent(language)),
+                    )?;
+
+                    file_names.push(companion_file.file_name.to_string());
+                }
+
+                Ok(WrittenXml {
+                    language_code: language.to_string(),
+                    sub_dir: sub_dir_name,
+                    file_names,
+                })
+            }
+        }
+
+        #[derive(Debug, Clone, Default, PartialEq, Eq)]
+        pub struct LocalizationValue {
+            pub language_translation: BTreeMap<String, Translation>,
+        }
+
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct SinglePluralVariation {
+            pub variate: PluralVariate,
+            pub translation_value: TranslationValue,
+        }
+
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct SingleDeviceVariation {
+            pub variate: DeviceVariate,
+            pub translation_value: TranslationValue,
+        }
+
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub enum Translation {
+            Localization(TranslationValue),
+            PluralVariation(Vec<SinglePluralVariation>),
+            /// A value per device family. Android has no such concept, it gets
+            /// [Translation::device_fallback].
+            DeviceVariation(Vec<SingleDeviceVariation>),
+        }
+
+        impl Translation {
+            pub fn expect_localization(self) -> TranslationValue {
+                match self {
+                    Translation::Localization(tv) => tv,
+                    _ => panic!(),
+                }
+            }
+
+            pub fn expect_plural_variation(self) -> Vec<SinglePluralVariation> {
+                match self {
+                    Translation::PluralVariation(pv) => pv,
+                    _ => panic!(),
+                }
+            }
+
+            /// The value, plurals are rendered as `one: ...; other: ...` and device variations as
+            /// `iphone: ...; other: ...`
+            pub fn display_value(&self) -> String {
+                match self {
+                    Translation::Localization(l) => l.value.to_string(),
+                    Translation::PluralVariation(pv) => pv
+                        .iter()
+                        .map(|single| {
+                            format!(
+                                "{}: {}",
+                                single.variate.android_key(),
+                                single.translation_value.value
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join("; "),
+                    Translation::DeviceVariation(dv) => dv
+                        .iter()
+                        .map(|single| {
+                            format!(
+                                "{}: {}",
+                                single.variate.apple_key(),
+                                single.translation_value.value
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join("; "),
+                }
+            }
+
+            /// For device variations the `other` value, or the first one when there's no `other`
+            pub fn device_fallback(&self) -> Option<&TranslationValue> {
+                match self {
+                    Translation::DeviceVariation(dv) => dv
+                        .iter()
+                        .find(|single| single.variate == DeviceVariate::Other)
+                        .or(dv.first())
+                        .map(|single| &single.translation_value),
+                    _ => None,
+                }
+            }
+
+            /// The value where a single string is expected: the localization itself or the
+            /// [Translation::device_fallback]. `None` for plurals.
+            pub fn simple_value(&self) -> Option<&TranslationValue> {
+                match self {
+                    Translation::Localization(l) => Some(l),
+                    Translation::PluralVariation(_) => None,
+                    Translation::DeviceVariation(_) => self.device_fallback(),
+                }
+            }
+
+            /// True when every value (every plural case) has the `translated` state
+            pub fn is_translated(&self) -> bool {
+                self.translation_values()
+                    .iter()
+                    .all(|tv| tv.state == crate::TRANSLATED_STATE)
+            }
+
+            pub fn translation_values(&self) -> Vec<&TranslationValue> {
+                match self {
+                    Translation::Localization(l) => vec![l],
+                    Translation::PluralVariation(pv) => {
+                        pv.iter().map(|single| &single.translation_value).collect()
+                    }
+                    Translation::DeviceVariation(dv) => {
+                        dv.iter().map(|single| &single.translation_value).collect()
+                    }
+                }
+            }
+
+            pub fn translation_values_mut(&mut self) -> Vec<&mut TranslationValue> {
+                match self {
+                    Translation::Localization(l) => vec![l],
+                    Translation::PluralVariation(pv) => pv
+                        .iter_mut()
+                        .map(|single| &mut single.translation_value)
+                        .collect(),
+                    Translation::DeviceVariation(dv) => dv
+                        .iter_mut()
+                        .map(|single| &mut single.translation_value)
+                        .collect(),
+                }
+            }
+
+            pub fn word_count(&self) -> usize {
+                match self {
+                    Translation::Localization(l) => words_count::count(&l.value).words,
+                    Translation::PluralVariation(_) | Translation::DeviceVariation(_) => self
+                        .translation_values()
+                        .iter()
+                        .map(|tv| words_count::count(&tv.value).words)
+                        .sum(),
+                }
+            }
+        }
+
+        /// The device families of a device variation, in the order Xcode lists them
+        #[derive(Debug, Clone, Copy, Eq, Hash, PartialEq, PartialOrd, Ord, EnumConstValue)]
+        pub enum DeviceVariate {
+            IPhone,
+            IPad,
+            Mac,
+            AppleTv,
+            Watch,
+            AppleVision,
+            Other,
+        }
+
+        impl DeviceVariate {
+            /// The key in `.xcstrings`, e.g. `iphone`
+            pub fn apple_key(&self) -> &'static str {
+                match self {
+                    DeviceVariate::IPhone => "iphone",
+                    DeviceVariate::IPad => "ipad",
+                    DeviceVariate::Mac => "mac",
+                    DeviceVariate::AppleTv => "appletv",
+                    DeviceVariate::Watch => "watch",
+                    DeviceVariate::AppleVision => "applevision",
+                    DeviceVariate::Other => "other",
+                }
+            }
+        }
+
+        #[derive(Debug, Clone, Eq, Hash, PartialEq, EnumConstValue)]
+        pub enum PluralVariate {
+            Zero,
+            One,
+            Two,
+            Few,
+            Many,
+            Other,
+        }
+
+        impl PluralVariate {
+            pub fn from_android_key(str: &str) -> Option<Self> {
+                PluralVariate::all_values()
+                    .into_iter()
+                    .find(|variate| variate.android_key() == str)
+            }
+
+            pub fn android_key(&self) -> &'static str {
+                match self {
+                    PluralVariate::Zero => "zero",
+                    PluralVariate::One => "one",
+                    PluralVariate::Two => "two",
+                    PluralVariate::Few => "few",
+                    PluralVariate::Many => "many",
+                    PluralVariate::Other => "other",
+                }
+            }
+
+            /// The CLDR categories `language` needs for cardinal numbers, e.g. `one` and `other`
+            /// for English. Covers the common languages, others default to `one` and `other`.
+            pub fn required_for(language: &str) -> &'static [PluralVariate] {
+                use PluralVariate::*;
+
+                let base = language.split(['-', '_']).next().unwrap_or_default();
+
+                match base.to_lowercase().as_str() {
+                    "ja" | "zh" | "ko" | "th" | "vi" | "id" | "ms" | "lo" | "my" => &[Other],
+                    "fr" | "es" | "it" | "pt" | "ca" => &[One, Many, Other],
+                    "ru" | "uk" | "be" | "pl" | "cs" | "sk" | "lt" => &[One, Few, Many, Other],
+                    "hr" | "sr" | "bs" | "ro" => &[One, Few, Other],
+                    "he" | "iw" => &[One, Two, Other],
+                    "ga" => &[One, Two, Few, Many, Other],
+                    "ar" | "cy" => &[Zero, One, Two, Few, Many, Other],
+                    _ => &[One, Other],
+                }
+            }
+
+            /// The position in the canonical CLDR sequence, zero first and other last
+            pub fn order(&self) -> u8 {
+                match self {
+                    PluralVariate::Zero => 0,
+                    PluralVariate::One => 1,
+                    PluralVariate::Two => 2,
+                    PluralVariate::Few => 3,
+                    PluralVariate::Many => 4,
+                    PluralVariate::Other => 5,
+                }
+            }
+        }
+
+        impl PartialOrd for PluralVariate {
+            fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for PluralVariate {
+            fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+                self.order().cmp(&other.order())
+            }
+        }
+
+        /// The order of the `<item>`s in a `<plurals>`, Android itself doesn't care
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+        #[serde(rename_all = "snake_case")]
+        pub enum PluralOrder {
+            /// The CLDR order, see [PluralVariate::order]
+            #[default]
+            Canonical,
+            /// The order of the plural, with only `other` moved to the end
+            OtherLast,
+        }
+
+        impl PluralOrder {
+            pub fn sort(&self, plural: &mut [SinglePluralVariation]) {
+                match self {
+                    PluralOrder::Canonical => plural.sort_by(|a, b| a.variate.cmp(&b.variate)),
+                    PluralOrder::OtherLast => {
+                        plural.sort_by_key(|single| single.variate == PluralVariate::Other)
+                    }
+                }
+            }
+        }
+
+        /// Android collapses runs of unescaped whitespace to a single space at runtime
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+        #[serde(rename_all = "snake_case")]
+        pub enum WhitespaceHandling {
+            /// Emit the whitespace as is
+            #[default]
+            Preserve,
+            /// Escape every space of a run as `\u0020`, so Android keeps all of them
+            Escape,
+            /// Emit the whitespace as is, but log a warning for runs of multiple spaces
+            Warn,
+        }
+
+        /// Applies [WhitespaceHandling] to an already sanitized value
+        pub fn handle_whitespace(sanitized: &str, whitespace: WhitespaceHandling) -> String {
+            if whitespace == WhitespaceHandling::Preserve || !sanitized.contains("  ") {
+                return sanitized.to_string();
+            }
+
+            if whitespace == WhitespaceHandling::Warn {
+                log::warn!(
+                    "Value contains multiple consecutive spaces which Android collapses: {}",
+                    sanitized
+                );
+
+                return sanitized.to_string();
+            }
+
+            cached_regex!(re, r" {2,}");
+
+            re.replace_all(sanitized, |captures: &regex::Captures| {
+                "\\u0020".repeat(captures[0].len())
+            })
+            .to_string()
+        }
+
+        /// Converts the specifiers and escapes the apostrophes of a value for an Android
+        /// `<string>` element. Markup like `<b>` is kept, Android uses it for styling.
+        pub fn sanitize_for_android(value: &str) -> String {
+            crate::specifier::to_android(value).replace('\'', "\\'")
+        }
+
+        /// Escapes the XML special characters, for text which never contains markup
+        fn escape_xml(value: &str) -> String {
+            value
+                .replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+        }
+
+        /// XML comments can't contain `--`, which would also allow closing the comment early
+        fn escape_xml_comment(comment: &str) -> String {
+            let mut escaped = comment.to_string();
+
+            while escaped.contains("--") {
+                escaped = escaped.replace("--", "- -");
+            }
+
+            escaped
+        }
+
+        fn fill_missing_plural_from_other(plural: &mut Vec<SinglePluralVariation>, language: &str) {
+            let Some(other) = plural
+                .iter()
+                .find(|single| single.variate == PluralVariate::Other)
+                .map(|single| single.translation_value.clone())
+            else {
+                return;
+            };
+
+            for variate in PluralVariate::required_for(language) {
+                if !plural.iter().any(|single| &single.variate == variate) {
+                    plural.push(SinglePluralVariation {
+                        variate: variate.clone(),
+                        translation_value: other.clone(),
+                    });
+                }
+            }
+
+            plural.sort_by(|a, b| a.variate.cmp(&b.variate));
+        }
+
+        /// Like [sanitize_for_android], but the markup is kept in a CDATA section. A `]]>` in
+        /// the value is split over two sections, so it can't end the section early.
+        pub fn cdata_for_android(value: &str, whitespace: WhitespaceHandling) -> String {
+            let inner = handle_whitespace(
+                &crate::specifier::to_android(value).replace('\'', "\\'"),
+                whitespace,
+            );
+
+            format!("<![CDATA[{}]]>", inner.replace("]]>", "]]]]><![CDATA[>"))
+        }
+
+        impl TranslationValue {
+            pub fn sanitize_for_android(&self) -> String {
+                sanitize_for_android(&self.value)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::output::{
+        AndroidLocalizeConfig, AndroidWriteConfig, CompanionFile, DirectoryCasing, ParsedError,
+        PluralVariate, State, StateNames, WhitespaceHandling,
+    };
     use std::env::current_dir;
 
-    // Uncomment to update
+    // Uncomment to update
+    #[test]
+    fn update_android_xmls() {
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+        let current = current_dir().unwrap().join("test_resources");
+        let _ = parse_from_bytes(raw)
+            .unwrap()
+            .localizable
+            .localized_per_language()
+            .localized_for_android(AndroidLocalizeConfig {
+                write_config: Some(AndroidWriteConfig {
+                    write_in: current,
+                    only_write_language_code: None,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            });
+    }
+
+    #[test]
+    fn it_works() {
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+        let android_expected_en = include_bytes!("../test_resources/values/strings.xml");
+        let android_expected_nl = include_bytes!("../test_resources/values-nl/strings.xml");
+        let parsed = parse_from_bytes(raw).unwrap();
+        let localized_per_language = parsed.localizable.localized_per_language();
+        let android_actual = localized_per_language
+            .localized_for_android(Default::default())
+            .unwrap()
+            .sorted_languages;
+
+        for (language, value) in android_actual {
+            let expect = if language == parsed.localizable.source_language {
+                android_expected_en.to_vec()
+            } else {
+                assert_eq!(language, "nl");
+
+                android_expected_nl.to_vec()
+            };
+
+            let expect = String::from_utf8(expect).unwrap();
+
+            assert_eq!(value.trim(), expect.trim());
+        }
+    }
+
+    #[test]
+    fn stats_for_prefix() {
+        let raw = include_bytes!("../test_resources/Prefixed.xcstrings");
+        let stats = parse_from_bytes(raw)
+            .unwrap()
+            .localizable
+            .stats_for_prefix("onboarding.");
+
+        assert_eq!(stats.key_count, 2);
+        assert_eq!(stats.word_count_per_language["en"], 5);
+        assert_eq!(stats.word_count_per_language["nl"], 1);
+    }
+
+    #[test]
+    fn android_zero_quantity() {
+        let raw = r#"{
+            "sourceLanguage": "en",
+            "strings": {
+                "items": {
+                    "localizations": {
+                        "en": { "variations": { "plural": {
+                            "zero": { "stringUnit": { "state": "translated", "value": "No items" } },
+                            "other": { "stringUnit": { "state": "translated", "value": "%lld items" } }
+                        } } }
+                    }
+                }
+            },
+            "version": "1.0"
+        }"#;
+        let sorted_languages = parse_from_string(raw.to_string())
+            .unwrap()
+            .localizable
+            .localized_per_language()
+            .localized_for_android(Default::default())
+            .unwrap()
+            .sorted_languages;
+
+        assert!(sorted_languages["en"].contains("<item quantity=\"zero\">No items</item>"));
+        assert_eq!(
+            crate::types::output::PluralVariate::from_android_key("zero"),
+            Some(crate::types::output::PluralVariate::Zero)
+        );
+    }
+
+    #[test]
+    fn directory_casing() {
+        assert_eq!(DirectoryCasing::default(), DirectoryCasing::Android);
+        assert_eq!(
+            DirectoryCasing::Android.language_qualifier("pt-BR"),
+            "pt-rBR"
+        );
+        assert_eq!(
+            DirectoryCasing::Verbatim.language_qualifier("pt-BR"),
+            "pt-BR"
+        );
+        assert_eq!(DirectoryCasing::Android.language_qualifier("nl"), "nl");
+        assert_eq!(
+            DirectoryCasing::Android.language_qualifier("es-419"),
+            "es-r419"
+        );
+
+        let raw = r#"{
+            "sourceLanguage": "en",
+            "strings": {
+                "hello": {
+                    "localizations": {
+                        "pt-BR": { "stringUnit": { "state": "translated", "value": "Olá" } }
+                    }
+                }
+            },
+            "version": "1.0"
+        }"#;
+        let localized = parse_from_string(raw.to_string())
+            .unwrap()
+            .localizable
+            .localized_per_language();
+
+        for (casing, expected_dir) in [
+            (DirectoryCasing::Android, "values-pt-rBR"),
+            (DirectoryCasing::Verbatim, "values-pt-BR"),
+        ] {
+            let dir = std::env::temp_dir().join(format!("localizable_casing_{:?}", casing));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+
+            let written = localized
+                .localized_for_android(AndroidLocalizeConfig {
+                    write_config: Some(AndroidWriteConfig {
+                        write_in: dir.clone(),
+                        only_write_language_code: Some("pt-BR".to_string()),
+                        directory_casing: casing,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })
+                .unwrap()
+                .written_xmls;
+
+            assert_eq!(written[0].sub_dir, expected_dir);
+            assert!(dir.join(expected_dir).join("strings.xml").exists());
+        }
+    }
+
+    #[test]
+    fn leftover_ios_specifiers() {
+        assert_eq!(
+            specifier::ios_only_specifiers("%@ has %1$lld of %%@ %.2f"),
+            vec!["%@", "%1$lld"]
+        );
+        assert_eq!(
+            specifier::to_android("%1$@ scored %2$ld points, %lu left (100%%)"),
+            "%1$s scored %2$d points, %d left (100%%)"
+        );
+
+        let raw = r#"{
+            "sourceLanguage": "en",
+            "strings": {
+                "score": {
+                    "localizations": {
+                        "en": { "stringUnit": { "state": "translated", "value": "%1$@ scored %2$ld points, %lu left" } },
+                        "nl": { "stringUnit": { "state": "translated", "value": "%1$@ scoorde %2$-5llu punten" } }
+                    }
+                }
+            },
+            "version": "1.0"
+        }"#;
+        let localized = parse_from_string(raw.to_string())
+            .unwrap()
+            .localizable
+            .localized_per_language();
+
+        assert!(localized.leftover_ios_specifiers().is_empty());
+        assert_eq!(
+            specifier::android_leftovers("%#sfiles@ %@ %1$lld %D %S %d %1$s %.2f %%@"),
+            vec!["%#s", "%@", "%1$lld", "%D", "%S"]
+        );
+
+        let raw = r#"{
+            "sourceLanguage": "en",
+            "strings": {
+                "files": {
+                    "localizations": {
+                        "en": { "stringUnit": { "state": "translated", "value": "Found %#@files@ for %S" } }
+                    }
+                }
+            },
+            "version": "1.0"
+        }"#;
+        let leftovers: Vec<_> = parse_from_string(raw.to_string())
+            .unwrap()
+            .localizable
+            .localized_per_language()
+            .leftover_ios_specifiers()
+            .into_iter()
+            .map(|leftover| leftover.specifier)
+            .collect();
+
+        assert_eq!(leftovers, vec!["%#@files@", "%S"]);
+    }
+
+    #[test]
+    fn export_template_csv() {
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+        let csv = parse_from_bytes(raw)
+            .unwrap()
+            .localizable
+            .export_template_csv("de");
+        let lines: Vec<_> = csv.lines().collect();
+
+        assert_eq!(lines[0], "key,comment,en,de");
+        assert!(lines.contains(
+            &"Inlined with an actual translation,some random comment,Inlined with an actual translation,"
+        ));
+        assert!(lines.contains(&"amount_favorite_riddles[one],,%1$lld favorite riddle,"));
+        assert!(lines.contains(&"average_guessing_time,,Average guessing time,"));
+
+        for line in &lines[1..] {
+            assert!(line.ends_with(','));
+            assert!(!line.split(',').nth(2).unwrap().is_empty());
+        }
+    }
+
+    #[test]
+    fn compiles_without_std() {
+        let output = std::process::Command::new(env!("CARGO"))
+            .args(["check", "--lib", "--no-default-features"])
+            .current_dir(env!("CARGO_MANIFEST_DIR"))
+            .env(
+                "CARGO_TARGET_DIR",
+                PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                    .join("target")
+                    .join("no_std"),
+            )
+            .output()
+            .unwrap();
+
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    #[test]
+    fn all_comments() {
+        let raw = include_bytes!("../test_resources/Comments.xcstrings");
+        let comments = parse_from_bytes(raw).unwrap().localizable.all_comments();
+
+        assert_eq!(
+            comments["Title of a screen"],
+            vec!["profile_title", "settings_title"]
+        );
+        assert_eq!(comments["Shown on the save button"], vec!["save"]);
+        assert_eq!(comments[""], vec!["cancel"]);
+    }
+
+    #[test]
+    fn custom_state_names() {
+        let raw = r#"{
+            "sourceLanguage": "en",
+            "strings": {
+                "hello": {
+                    "localizations": {
+                        "en": { "stringUnit": { "state": "done", "value": "Hello" } },
+                        "nl": { "stringUnit": { "state": "needs-review", "value": "Hallo" } }
+                    }
+                }
+            },
+            "version": "1.0"
+        }"#;
+        let config = ParseConfig {
+            state_names: StateNames {
+                translated: "done".to_string(),
+                needs_review: "needs-review".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let parsed = parse_from_string_with_config(raw.to_string(), &config).unwrap();
+        let hello = &parsed.localizable.single_translation[0];
+        let state = |language: &str| {
+            hello
+                .translation_for(language)
+                .unwrap()
+                .clone()
+                .expect_localization()
+                .state
+        };
+
+        assert_eq!(state("en"), TRANSLATED_STATE);
+        assert_eq!(state("nl"), NEEDS_REVIEW_STATE);
+        assert_eq!(
+            config.state_names.denormalize(NEEDS_REVIEW_STATE),
+            "needs-review"
+        );
+        assert!(serde_json::to_string(&parsed.translation)
+            .unwrap()
+            .contains("needs-review"));
+
+        let saved = parsed
+            .localizable
+            .to_xcstrings_with_state_names(&config.state_names);
+
+        assert!(saved.contains("\"state\": \"needs-review\""));
+        assert!(!saved.contains(NEEDS_REVIEW_STATE));
+
+        let reparsed = parse_from_string_with_config(saved, &config).unwrap();
+
+        assert_eq!(
+            reparsed.localizable.single_translation[0].localization_value,
+            hello.localization_value
+        );
+    }
+
+    #[test]
+    fn file_groups() {
+        let raw = include_bytes!("../test_resources/Prefixed.xcstrings");
+        let dir = std::env::temp_dir().join("localizable_file_groups");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let localized_for_android = parse_from_bytes(raw)
+            .unwrap()
+            .localizable
+            .localized_per_language()
+            .localized_for_android(AndroidLocalizeConfig {
+                file_groups: [("onboarding.".to_string(), "strings_onboarding".to_string())]
+                    .into_iter()
+                    .collect(),
+                write_config: Some(AndroidWriteConfig {
+                    write_in: dir.clone(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .unwrap();
+
+        for written in &localized_for_android.written_xmls {
+            assert_eq!(
+                written.file_names,
+                vec!["strings.xml", "strings_onboarding.xml"]
+            );
+
+            let sub_dir = dir.join(&written.sub_dir);
+            let onboarding =
+                std::fs::read_to_string(sub_dir.join("strings_onboarding.xml")).unwrap();
+            let strings = std::fs::read_to_string(sub_dir.join("strings.xml")).unwrap();
+
+            assert!(onboarding.contains("onboarding_title"));
+            assert!(!onboarding.contains("settings_title"));
+            assert!(strings.contains("settings_title"));
+            assert!(!strings.contains("onboarding_title"));
+        }
+
+        assert_eq!(localized_for_android.written_xmls.len(), 2);
+    }
+
+    #[test]
+    fn control_characters() {
+        let raw = include_bytes!("../test_resources/ControlCharacters.xcstrings");
+        let issues = parse_from_bytes(raw)
+            .unwrap()
+            .localizable
+            .control_characters();
+
+        assert_eq!(
+            issues,
+            vec![validate::ControlCharacterIssue {
+                key_raw: "bell".to_string(),
+                language: "nl".to_string(),
+                code_point: 0x07,
+            }]
+        );
+
+        let stripped = parse_from_bytes_with_config(
+            raw,
+            &ParseConfig {
+                strip_control_characters: true,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .localizable;
+
+        assert!(stripped.control_characters().is_empty());
+        assert_eq!(
+            stripped.single_translation[0]
+                .translation_for("nl")
+                .unwrap()
+                .clone()
+                .expect_localization()
+                .value,
+            "Bel\tnu\n"
+        );
+    }
+
+    #[test]
+    fn apply_android_edits() {
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+        let mut parsed = parse_from_bytes(raw).unwrap();
+        let xml = r#"<resources>
+<string name="average_guessing_time">Gemiddelde \'raadtijd\'</string>
+<plurals name="amount_favorite_riddles">
+<item quantity="one">%1$d lievelingsraadsel</item>
+<item quantity="other">%1$d lievelingsraadsels</item>
+</plurals>
+<string name="unknown_key">Onbekend</string>
+</resources>"#;
+
+        let not_found = parsed.apply_android_edits(xml, "nl").unwrap();
+
+        assert_eq!(not_found, vec!["unknown_key"]);
+
+        let translation = |key: &str| {
+            parsed
+                .localizable
+                .single_translation
+                .iter()
+                .find(|st| st.key_raw == key)
+                .unwrap()
+                .translation_for("nl")
+                .unwrap()
+                .clone()
+        };
+        let average = translation("average_guessing_time").expect_localization();
+
+        assert_eq!(average.value, "Gemiddelde 'raadtijd'");
+        assert_eq!(average.state, NEEDS_REVIEW_STATE);
+
+        let plural = translation("amount_favorite_riddles").expect_plural_variation();
+
+        assert_eq!(
+            plural[0].translation_value.value,
+            "%1$lld lievelingsraadsel"
+        );
+        assert_eq!(plural[1].translation_value.state, NEEDS_REVIEW_STATE);
+        assert!(serde_json::to_string(&parsed.translation)
+            .unwrap()
+            .contains("Gemiddelde 'raadtijd'"));
+    }
+
+    #[test]
+    fn positional_specifier_issues() {
+        let raw = r#"{
+            "sourceLanguage": "en",
+            "strings": {
+                "invite": {
+                    "localizations": {
+                        "en": { "stringUnit": { "state": "translated", "value": "%1$@ invited %2$@" } },
+                        "de": { "stringUnit": { "state": "translated", "value": "%2$@ wurde von %1$@ eingeladen" } },
+                        "nl": { "stringUnit": { "state": "translated", "value": "%2$@ is uitgenodigd" } }
+                    }
+                }
+            },
+            "version": "1.0"
+        }"#;
+        let issues = parse_from_string(raw.to_string())
+            .unwrap()
+            .localizable
+            .positional_specifier_issues();
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].language, "nl");
+        assert_eq!(issues[0].gaps(), vec![1]);
+        assert!(issues[0].extras().is_empty());
+        assert_eq!(
+            specifier::argument_positions("%@ and %@ or %3$d"),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn sanitized_app_name() {
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+        let config = AndroidLocalizeConfig {
+            app_name: "Jasper's Riddles & Co".to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(config.sanitized_app_name(), "Jasper\\'s Riddles &amp; Co");
+
+        let sorted_languages = parse_from_bytes(raw)
+            .unwrap()
+            .localizable
+            .localized_per_language()
+            .localized_for_android(config)
+            .unwrap()
+            .sorted_languages;
+
+        assert!(sorted_languages["en"]
+            .contains("<string name=\"app_name\">Jasper\\'s Riddles &amp; Co</string>"));
+    }
+
+    #[test]
+    fn table_name() {
+        let file = current_dir()
+            .unwrap()
+            .join("test_resources")
+            .join("Localizable.xcstrings");
+        let parsed = parse_from_file(&file).unwrap();
+
+        assert_eq!(parsed.table_name.as_deref(), Some("Localizable"));
+        assert!(parse_from_bytes(
+            b"{\"sourceLanguage\":\"en\",\"strings\":{},\"version\":\"1.0\"}"
+        )
+        .unwrap()
+        .table_name
+        .is_none());
+
+        let dir = std::env::temp_dir().join("localizable_table_name");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let written = parsed
+            .localizable
+            .localized_per_language()
+            .localized_for_android(AndroidLocalizeConfig {
+                write_config: Some(AndroidWriteConfig {
+                    write_in: dir.clone(),
+                    table_name: parsed.table_name.clone(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .unwrap()
+            .written_xmls;
+
+        assert_eq!(written[0].file_names, vec!["localizable.xml"]);
+        assert!(dir.join("values").join("localizable.xml").exists());
+    }
+
+    #[test]
+    fn coverage_diff() {
+        let raw = r#"{
+            "sourceLanguage": "en",
+            "strings": {
+                "both": {
+                    "localizations": {
+                        "es": { "stringUnit": { "state": "translated", "value": "Ambos" } },
+                        "es-MX": { "stringUnit": { "state": "translated", "value": "Ambos" } }
+                    }
+                },
+                "only_es": {
+                    "localizations": {
+                        "es": { "stringUnit": { "state": "translated", "value": "Solo" } },
+                        "es-MX": { "stringUnit": { "state": "new", "value": "" } }
+                    }
+                },
+                "only_mx": {
+                    "localizations": {
+                        "es-MX": { "stringUnit": { "state": "translated", "value": "Ahorita" } }
+                    }
+                }
+            },
+            "version": "1.0"
+        }"#;
+        let localizable = parse_from_string(raw.to_string()).unwrap().localizable;
+        let diff = localizable.coverage_diff("es", "es-MX");
+
+        assert_eq!(diff.only_in_a, vec!["only_es"]);
+        assert_eq!(diff.only_in_b, vec!["only_mx"]);
+        assert!(localizable.coverage_diff("es", "es").is_equal());
+    }
+
+    #[test]
+    fn transition_states() {
+        let raw = r#"{
+            "sourceLanguage": "en",
+            "strings": {
+                "apples": {
+                    "localizations": {
+                        "nl": { "variations": { "plural": {
+                            "one": { "stringUnit": { "state": "needs_review", "value": "%lld appel" } },
+                            "other": { "stringUnit": { "state": "needs_review", "value": "%lld appels" } }
+                        } } },
+                        "de": { "stringUnit": { "state": "needs_review", "value": "Äpfel" } }
+                    }
+                },
+                "pears": {
+                    "localizations": {
+                        "nl": { "stringUnit": { "state": "needs_review", "value": "Peren" } },
+                        "de": { "stringUnit": { "state": "translated", "value": "Birnen" } }
+                    }
+                }
+            },
+            "version": "1.0"
+        }"#;
+        let mut localizable = parse_from_string(raw.to_string()).unwrap().localizable;
+
+        assert_eq!(
+            localizable.transition_states(
+                State::NeedsReview,
+                State::Translated,
+                &["nl".to_string()]
+            ),
+            3
+        );
+        assert_eq!(
+            localizable.transition_states(State::NeedsReview, State::Translated, &[]),
+            1
+        );
+        assert_eq!(
+            localizable.transition_states(State::NeedsReview, State::Translated, &[]),
+            0
+        );
+    }
+
+    #[test]
+    fn export_untranslated() {
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+        let exported = parse_from_bytes(raw).unwrap().export_untranslated("nl");
+        let keys: Vec<_> = exported
+            .localizable
+            .single_translation
+            .iter()
+            .map(|st| st.key_raw.as_str())
+            .collect();
+
+        assert_eq!(
+            keys,
+            vec![
+                "Inlined no translation % weird charss:",
+                "Inlined with an inlined  base language"
+            ]
+        );
+        assert_eq!(exported.translation.strings.len(), 2);
+
+        let json = serde_json::to_string(&exported.translation).unwrap();
+        let reparsed = parse_from_string(json).unwrap();
+
+        assert_eq!(reparsed.localizable.single_translation.len(), 2);
+        assert_eq!(
+            reparsed.localizable.single_translation[1]
+                .translation_for("en")
+                .unwrap()
+                .clone()
+                .expect_localization()
+                .value,
+            "Keys to localize: %1$lld (%2$lld%%)"
+        );
+    }
+
+    #[test]
+    fn xml_declaration() {
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+        let localized = parse_from_bytes(raw)
+            .unwrap()
+            .localizable
+            .localized_per_language();
+        let declaration = "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<resources>";
+
+        let with_declaration = localized
+            .localized_for_android(AndroidLocalizeConfig {
+                xml_declaration: true,
+                ..Default::default()
+            })
+            .unwrap()
+            .sorted_languages;
+        let without_declaration = localized
+            .localized_for_android(Default::default())
+            .unwrap()
+            .sorted_languages;
+
+        assert!(with_declaration["nl"].starts_with(declaration));
+        assert!(without_declaration["nl"].starts_with("<resources>"));
+    }
+
+    #[test]
+    fn assert_key_parity() {
+        let raw = r#"{
+            "sourceLanguage": "en",
+            "strings": {
+                "complete": {
+                    "localizations": {
+                        "en": { "stringUnit": { "state": "translated", "value": "Complete" } },
+                        "nl": { "stringUnit": { "state": "translated", "value": "Compleet" } }
+                    }
+                },
+                "missing": {
+                    "localizations": {
+                        "en": { "stringUnit": { "state": "translated", "value": "Missing" } }
+                    }
+                },
+                "extra": {
+                    "localizations": {
+                        "nl": { "stringUnit": { "state": "translated", "value": "Extra" } }
+                    }
+                }
+            },
+            "version": "1.0"
+        }"#;
+        let mut localizable = parse_from_string(raw.to_string()).unwrap().localizable;
+
+        // The parser falls back to the key for a missing source value, remove it to end up
+        // with a key that only exists in the target language
+        localizable
+            .single_translation
+            .iter_mut()
+            .find(|st| st.key_raw == "extra")
+            .unwrap()
+            .localization_value
+            .language_translation
+            .remove("en");
+
+        let issues = localizable.assert_key_parity().unwrap_err();
+
+        assert_eq!(
+            issues,
+            vec![
+                validate::KeyParityIssue {
+                    key_raw: "extra".to_string(),
+                    language: "nl".to_string(),
+                    kind: validate::KeyParityKind::Extra,
+                },
+                validate::KeyParityIssue {
+                    key_raw: "missing".to_string(),
+                    language: "nl".to_string(),
+                    kind: validate::KeyParityKind::Missing,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn script_subtags() {
+        let casing = DirectoryCasing::Android;
+
+        assert_eq!(casing.language_qualifier("zh-Hans"), "b+zh+Hans");
+        assert_eq!(casing.language_qualifier("sr-Latn"), "b+sr+Latn");
+        assert_eq!(casing.language_qualifier("zh-hant-tw"), "b+zh+Hant+TW");
+        assert_eq!(casing.language_qualifier("pt-BR"), "pt-rBR");
+        assert_eq!(
+            DirectoryCasing::Verbatim.language_qualifier("zh-Hans"),
+            "zh-Hans"
+        );
+    }
+
+    #[test]
+    fn plural_variate_order() {
+        let mut variates = [
+            PluralVariate::Other,
+            PluralVariate::Two,
+            PluralVariate::Zero,
+            PluralVariate::Many,
+            PluralVariate::One,
+            PluralVariate::Few,
+        ];
+
+        variates.sort();
+
+        assert_eq!(variates, PluralVariate::all_values());
+        assert!(PluralVariate::One < PluralVariate::Other);
+    }
+
+    #[test]
+    fn digit_leading_android_keys() {
+        let raw = include_bytes!("../test_resources/LeadingDigit.xcstrings");
+        let localizable = parse_from_bytes(raw).unwrap().localizable;
+
+        assert_eq!(localizable.digit_leading_android_keys(), vec!["1st place"]);
+
+        let fixed = parse_from_bytes_with_config(
+            raw,
+            &ParseConfig {
+                leading_digit_prefix: Some("_".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .localizable;
+
+        assert!(fixed.digit_leading_android_keys().is_empty());
+        assert_eq!(fixed.single_translation[0].key_alphanumeric, "_1st_place");
+        assert_eq!(fixed.single_translation[1].key_alphanumeric, "second_place");
+    }
+
+    #[test]
+    fn diff_to_markdown() {
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+        let old = parse_from_bytes(raw).unwrap().localizable;
+        let mut new = old.clone();
+
+        new.single_translation
+            .retain(|st| st.key_raw != "average_guessing_time");
+        new.single_translation[0]
+            .localization_value
+            .language_translation
+            .insert(
+                "nl".to_string(),
+                types::output::Translation::Localization(types::inoutoutput::TranslationValue {
+                    state: TRANSLATED_STATE.to_string(),
+                    value: "Niet | vertaald".to_string(),
+                }),
+            );
+
+        let mut added = new.single_translation[0].clone();
+
+        added.key_raw = "new_key".to_string();
+        new.single_translation.push(added);
+
+        let diff = old.diff(&new);
+
+        assert_eq!(diff.removed, vec!["average_guessing_time"]);
+        assert_eq!(diff.changed.len(), 1);
+
+        let markdown = diff.to_markdown();
+
+        assert!(markdown.contains(
+            "## Added\n\n| Key | Source |\n| --- | --- |\n| new_key | Inlined no translation % weird charss: |"
+        ));
+        assert!(markdown.contains("| average_guessing_time |"));
+        assert!(markdown.contains("| Niet \\| vertaald |"));
+    }
+
+    #[test]
+    fn strict_placeholders() {
+        let raw = r#"{
+            "sourceLanguage": "en",
+            "strings": {
+                "greeting": {
+                    "localizations": {
+                        "en": { "stringUnit": { "state": "translated", "value": "Hello %@, you have %lld points" } },
+                        "nl": { "stringUnit": { "state": "translated", "value": "Hallo %@" } }
+                    }
+                }
+            },
+            "version": "1.0"
+        }"#;
+        let localized = parse_from_string(raw.to_string())
+            .unwrap()
+            .localizable
+            .localized_per_language();
+
+        assert!(localized.localized_for_android(Default::default()).is_ok());
+
+        let error = localized
+            .localized_for_android(AndroidLocalizeConfig {
+                strict_placeholders: true,
+                ..Default::default()
+            })
+            .unwrap_err();
+
+        match error {
+            ParsedError::PlaceholderMismatch(issues) => {
+                assert_eq!(issues.len(), 1);
+                assert_eq!(issues[0].key_raw, "greeting");
+                assert_eq!(issues[0].language, "nl");
+            }
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn source_language_from_env() {
+        let raw = include_bytes!("../test_resources/NoSourceLanguage.xcstrings");
+        let explicit_config = ParseConfig {
+            default_source_language: Some("de".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            ParseConfig::default().resolve_default_source_language_with(Some("nl")),
+            "nl"
+        );
+        assert_eq!(
+            explicit_config.resolve_default_source_language_with(Some("nl")),
+            "de"
+        );
+        assert_eq!(
+            ParseConfig::default().resolve_default_source_language_with(Some("")),
+            DEFAULT_SOURCE_LANGUAGE
+        );
+        assert_eq!(
+            ParseConfig::default().resolve_default_source_language_with(None),
+            DEFAULT_SOURCE_LANGUAGE
+        );
+
+        let explicit = parse_from_bytes_with_config(raw, &explicit_config).unwrap();
+
+        assert_eq!(explicit.localizable.source_language, "de");
+        assert_eq!(explicit.translation.source_language, "de");
+    }
+
+    #[test]
+    fn argument_arity_histogram() {
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+        let histogram = parse_from_bytes(raw)
+            .unwrap()
+            .localizable
+            .argument_arity_histogram();
+
+        assert_eq!(
+            histogram,
+            [(0, 3), (1, 1), (2, 1)]
+                .into_iter()
+                .collect::<BTreeMap<_, _>>()
+        );
+    }
+
+    #[test]
+    fn to_strings_file() {
+        let raw = r#"{
+            "sourceLanguage": "en",
+            "strings": {
+                "quote": {
+                    "comment": "Shown in a */ bubble",
+                    "localizations": {
+                        "en": { "stringUnit": { "state": "translated", "value": "He said \"hi\"\nand left \\o/" } }
+                    }
+                },
+                "apples": {
+                    "localizations": {
+                        "en": { "variations": { "plural": {
+                            "other": { "stringUnit": { "state": "translated", "value": "%lld apples" } }
+                        } } }
+                    }
+                }
+            },
+            "version": "1.0"
+        }"#;
+        let strings = parse_from_string(raw.to_string())
+            .unwrap()
+            .localizable
+            .to_strings_file("en");
+
+        assert_eq!(
+            strings,
+            "/* Shown in a * / bubble */\n\"quote\" = \"He said \\\"hi\\\"\\nand left \\\\o/\";"
+        );
+    }
+
+    #[test]
+    fn merge_plural_cases() {
+        let plural = |cases: &[(&str, &str)]| {
+            let cases: Vec<_> = cases
+                .iter()
+                .map(|(variate, value)| {
+                    format!(r#""{variate}": {{ "stringUnit": {{ "state": "translated", "value": "{value}" }} }}"#)
+                })
+                .collect();
+
+            format!(
+                r#"{{ "sourceLanguage": "en", "strings": {{ "apples": {{ "localizations": {{
+                    "en": {{ "variations": {{ "plural": {{ {} }} }} }}
+                }} }} }}, "version": "1.0" }}"#,
+                cases.join(",")
+            )
+        };
+        let existing = parse_from_string(plural(&[("one", "One apple"), ("other", "")]))
+            .unwrap()
+            .localizable;
+        let incoming =
+            parse_from_string(plural(&[("zero", "No apples"), ("other", "%lld apples")]))
+                .unwrap()
+                .localizable;
+
+        let mut merged = existing.clone();
+        let report = merged.merge(&incoming, merge::MergeStrategy::OnlyFillEmpty);
+
+        assert_eq!(
+            report.new_plural_cases,
+            vec![merge::NewPluralCase {
+                key_raw: "apples".to_string(),
+                language: "en".to_string(),
+                variate: PluralVariate::Zero,
+            }]
+        );
+
+        let cases = merged.single_translation[0]
+            .translation_for("en")
+            .unwrap()
+            .clone()
+            .expect_plural_variation();
+        let values: Vec<_> = cases
+            .iter()
+            .map(|c| (c.variate.clone(), c.translation_value.value.as_str()))
+            .collect();
+
+        assert_eq!(
+            values,
+            vec![
+                (PluralVariate::Zero, "No apples"),
+                (PluralVariate::One, "One apple"),
+                (PluralVariate::Other, "%lld apples"),
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_file_name() {
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+        let localized = parse_from_bytes(raw)
+            .unwrap()
+            .localizable
+            .localized_per_language();
+        let dir = std::env::temp_dir().join("localizable_custom_file_name");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let write = |file_name: &str| {
+            localized.localized_for_android(AndroidLocalizeConfig {
+                write_config: Some(AndroidWriteConfig {
+                    write_in: dir.clone(),
+                    file_name: Some(file_name.to_string()),
+                    table_name: Some("Localizable".to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+        };
+
+        let written = write("strings_generated.xml").unwrap().written_xmls;
+
+        assert_eq!(written[0].file_names, vec!["strings_generated.xml"]);
+        assert!(dir.join("values").join("strings_generated.xml").exists());
+        assert!(dir.join("values-nl").join("strings_generated.xml").exists());
+        assert!(matches!(
+            write("strings_generated.txt"),
+            Err(ParsedError::InvalidFileName(_))
+        ));
+
+        for file_name in [
+            "../../x.xml",
+            "sub/strings.xml",
+            "sub\\strings.xml",
+            "..xml",
+        ] {
+            assert!(matches!(
+                write(file_name),
+                Err(ParsedError::InvalidFileName(invalid)) if invalid == file_name
+            ));
+        }
+
+        assert!(!dir.join("x.xml").exists());
+        assert!(!dir.join("values").join("sub").exists());
+    }
+
+    #[test]
+    fn parse_with_metrics() {
+        let raw = include_str!("../test_resources/Localizable.xcstrings");
+        let (parsed, metrics) =
+            parse_from_string_with_metrics(raw.to_string(), &ParseConfig::default()).unwrap();
+
+        assert_eq!(metrics.key_count, 5);
+        assert_eq!(
+            metrics.key_count,
+            parsed.localizable.single_translation.len()
+        );
+        assert_eq!(metrics.language_count, 2);
+    }
+
+    #[test]
+    fn comment_as_value_suspects() {
+        let raw = include_bytes!("../test_resources/CommentAsValue.xcstrings");
+        let suspects = parse_from_bytes(raw)
+            .unwrap()
+            .localizable
+            .comment_as_value_suspects();
+
+        assert_eq!(suspects, vec!["welcome_message"]);
+    }
+
+    #[test]
+    fn whitespace_handling() {
+        let value = types::inoutoutput::TranslationValue {
+            state: TRANSLATED_STATE.to_string(),
+            value: "Score:  %lld".to_string(),
+        };
+        let sanitize = |whitespace: WhitespaceHandling| {
+            AndroidLocalizeConfig {
+                whitespace,
+                ..Default::default()
+            }
+            .sanitize(&value)
+        };
+
+        assert_eq!(sanitize(WhitespaceHandling::Preserve), "Score:  %d");
+        assert_eq!(sanitize(WhitespaceHandling::Warn), "Score:  %d");
+        assert_eq!(
+            sanitize(WhitespaceHandling::Escape),
+            "Score:\\u0020\\u0020%d"
+        );
+    }
+
+    #[test]
+    fn translations_for_key() {
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+        let localizable = parse_from_bytes(raw).unwrap().localizable;
+        let translations = localizable
+            .translations_for_key("average_guessing_time")
+            .unwrap();
+        let languages: Vec<_> = translations.language_translation.keys().collect();
+
+        assert_eq!(languages, vec!["en", "nl"]);
+        assert_eq!(
+            translations.language_translation["nl"],
+            types::output::Translation::Localization(types::inoutoutput::TranslationValue {
+                state: TRANSLATED_STATE.to_string(),
+                value: "Gemiddelde tijd voor oplossen".to_string(),
+            })
+        );
+        assert!(localizable.translations_for_key("missing").is_none());
+    }
+
+    #[test]
+    fn assert_source_complete() {
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+        let mut localizable = parse_from_bytes(raw).unwrap().localizable;
+
+        assert!(localizable.assert_source_complete().is_ok());
+
+        localizable.single_translation[1]
+            .localization_value
+            .language_translation
+            .remove("en");
+
+        assert_eq!(
+            localizable.assert_source_complete().unwrap_err(),
+            vec!["Inlined with an actual translation"]
+        );
+    }
+
+    #[test]
+    fn tools_locale() {
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+        let sorted_languages = parse_from_bytes(raw)
+            .unwrap()
+            .localizable
+            .localized_per_language()
+            .localized_for_android(AndroidLocalizeConfig {
+                tools_locale: true,
+                ..Default::default()
+            })
+            .unwrap()
+            .sorted_languages;
+
+        assert!(sorted_languages["nl"].starts_with(
+            "<resources xmlns:tools=\"http://schemas.android.com/tools\" tools:locale=\"nl\">"
+        ));
+        assert!(sorted_languages["en"].contains("tools:locale=\"en\""));
+
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "title" : { "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Title" } },
+      "pt-BR" : { "stringUnit" : { "state" : "translated", "value" : "Título" } },
+      "zh-Hans" : { "stringUnit" : { "state" : "translated", "value" : "标题" } }
+    } }
+  },
+  "version" : "1.0"
+}"#;
+        let sorted_languages = parse_from_string(raw.to_string())
+            .unwrap()
+            .localizable
+            .localized_per_language()
+            .localized_for_android(AndroidLocalizeConfig {
+                tools_locale: true,
+                ..Default::default()
+            })
+            .unwrap()
+            .sorted_languages;
+
+        assert!(sorted_languages["pt-BR"].contains("tools:locale=\"pt-rBR\""));
+        assert!(sorted_languages["zh-Hans"].contains("tools:locale=\"b+zh+Hans\""));
+    }
+
+    #[test]
+    fn convert_directory() {
+        let dir = std::env::temp_dir().join("localizable_convert_directory");
+        let input = dir.join("input");
+        let output = dir.join("output");
+
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&input).unwrap();
+        std::fs::create_dir_all(&output).unwrap();
+
+        std::fs::copy(
+            current_dir()
+                .unwrap()
+                .join("test_resources")
+                .join("Localizable.xcstrings"),
+            input.join("Localizable.xcstrings"),
+        )
+        .unwrap();
+        std::fs::copy(
+            current_dir()
+                .unwrap()
+                .join("test_resources")
+                .join("Prefixed.xcstrings"),
+            input.join("Prefixed.xcstrings"),
+        )
+        .unwrap();
+        std::fs::write(input.join("Broken.xcstrings"), "{").unwrap();
+        std::fs::write(input.join("ignored.json"), "{").unwrap();
+
+        let conversion = crate::convert_directory(
+            &input,
+            AndroidLocalizeConfig {
+                write_config: Some(AndroidWriteConfig {
+                    write_in: output.clone(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(conversion.converted.len(), 2);
+        assert_eq!(conversion.errors.len(), 1);
+        assert!(conversion.errors[0].0.ends_with("Broken.xcstrings"));
+        assert!(output.join("values").join("localizable.xml").exists());
+        assert!(output.join("values").join("prefixed.xml").exists());
+    }
+
+    #[test]
+    fn mixed_translation_kinds() {
+        use crate::validate::TranslationKind;
+
+        let raw = include_bytes!("../test_resources/MixedKinds.xcstrings");
+        let issues = parse_from_bytes(raw)
+            .unwrap()
+            .localizable
+            .mixed_translation_kinds();
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].key_raw, "apples");
+        assert_eq!(issues[0].kinds["en"], TranslationKind::PluralVariation);
+        assert_eq!(issues[0].kinds["nl"], TranslationKind::Localization);
+
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+
+        assert!(parse_from_bytes(raw)
+            .unwrap()
+            .localizable
+            .mixed_translation_kinds()
+            .is_empty());
+    }
+
+    #[test]
+    fn index() {
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+        let localizable = parse_from_bytes(raw).unwrap().localizable;
+        let index = localizable.build_index();
+
+        assert_eq!(localizable.single_translation.len(), index.len());
+
+        for st in &localizable.single_translation {
+            assert!(index.contains_key(&st.key_raw));
+            assert_eq!(
+                localizable.translations_for_key(&st.key_raw),
+                index.get(&st.key_raw).map(|st| &st.localization_value)
+            );
+        }
+
+        assert!(!index.contains_key("does_not_exist"));
+        assert!(index.get("does_not_exist").is_none());
+    }
+
+    #[test]
+    fn companion_file() {
+        let dir = std::env::temp_dir().join("localizable_companion_file");
+
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+        let localizable = parse_from_bytes(raw).unwrap().localizable;
+        let completion = localizable.completion_percent("nl");
+
+        assert!(completion > 0.0 && completion <= 100.0);
+        assert_eq!(localizable.completion_percent("de"), 0.0);
+
+        localizable
+            .localized_per_language()
+            .localized_for_android(AndroidLocalizeConfig {
+                write_config: Some(AndroidWriteConfig {
+                    write_in: dir.clone(),
+                    companion_file: Some(CompanionFile {
+                        file_name: "README.txt".to_string(),
+                        source_file: "Localizable.xcstrings".to_string(),
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let content = std::fs::read_to_string(dir.join("values-nl").join("README.txt")).unwrap();
+
+        assert!(content.contains("Generated at (unix time): "));
+        assert!(content.contains("Source file: Localizable.xcstrings\n"));
+        assert!(content.contains("Language: nl\n"));
+        assert!(content.contains(&format!("Completion: {completion:.1}%\n")));
+    }
+
+    #[test]
+    fn duplicate_plural_case() {
+        use crate::types::output::Translation;
+
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+        let mut localizable = parse_from_bytes(raw).unwrap().localizable;
+        let single_translation = localizable
+            .single_translation
+            .iter_mut()
+            .find(|st| st.key_raw == "amount_favorite_riddles")
+            .unwrap();
+        let Translation::PluralVariation(plural) = single_translation
+            .localization_value
+            .language_translation
+            .get_mut("nl")
+            .unwrap()
+        else {
+            panic!("Expected a plural");
+        };
+        let other = plural
+            .iter()
+            .find(|single| single.variate == PluralVariate::Other)
+            .unwrap()
+            .clone();
+
+        plural.push(other);
+
+        assert_eq!(
+            crate::validate::duplicate_plural_cases(plural),
+            vec![PluralVariate::Other]
+        );
+
+        let error = localizable
+            .localized_per_language()
+            .localized_for_android(Default::default())
+            .unwrap_err();
+
+        let ParsedError::DuplicatePluralCase(issue) = error else {
+            panic!("Expected a duplicate plural case, got {error}");
+        };
+
+        assert_eq!(issue.key_raw, "amount_favorite_riddles");
+        assert_eq!(issue.language, "nl");
+        assert_eq!(issue.variate, PluralVariate::Other);
+    }
+
+    #[test]
+    fn word_count_delta() {
+        let old = parse_from_string(
+            r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "kept" : { "localizations" : { "en" : { "stringUnit" : { "state" : "translated", "value" : "Stays the same" } } } },
+    "edited" : { "localizations" : { "en" : { "stringUnit" : { "state" : "translated", "value" : "Old text" } } } },
+    "removed" : { "localizations" : { "en" : { "stringUnit" : { "state" : "translated", "value" : "Goes away now" } } } }
+  },
+  "version" : "1.0"
+}"#
+            .to_string(),
+        )
+        .unwrap()
+        .localizable;
+        let new = parse_from_string(
+            r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "kept" : { "localizations" : { "en" : { "stringUnit" : { "state" : "needs_review", "value" : "Stays the same" } } } },
+    "edited" : { "localizations" : { "en" : { "stringUnit" : { "state" : "translated", "value" : "Some new text" } } } },
+    "added" : { "localizations" : { "en" : { "stringUnit" : { "state" : "translated", "value" : "A brand new sentence" } } } }
+  },
+  "version" : "1.0"
+}"#
+            .to_string(),
+        )
+        .unwrap()
+        .localizable;
+
+        assert_eq!(
+            crate::diff::word_count_delta(&old, &new, "en"),
+            crate::diff::WordDelta {
+                added: 4,
+                removed: 3,
+                changed: 3,
+            }
+        );
+        assert_eq!(
+            crate::diff::WordDelta::default(),
+            crate::diff::word_count_delta(&old, &old, "en")
+        );
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn to_yaml() {
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+        let yaml = parse_from_bytes(raw).unwrap().localizable.to_yaml();
+        let parsed: BTreeMap<String, BTreeMap<String, serde_yaml::Value>> =
+            serde_yaml::from_str(&yaml).unwrap();
+
+        assert_eq!(
+            parsed["amount_favorite_riddles"]["nl"]["one"],
+            "%1$lld favoriet raadsel"
+        );
+        assert_eq!(
+            parsed["average_guessing_time"]["nl"],
+            "Gemiddelde tijd voor oplossen"
+        );
+        assert_eq!(
+            parsed["Inlined no translation % weird charss:"]["en"],
+            "Inlined no translation % weird charss:"
+        );
+    }
+
+    #[test]
+    fn plural_type_conflicts() {
+        use crate::specifier::ArgumentType;
+
+        let raw = include_bytes!("../test_resources/PluralTypeConflict.xcstrings");
+        let conflicts = parse_from_bytes(raw)
+            .unwrap()
+            .localizable
+            .plural_type_conflicts();
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].key_raw, "items");
+        assert_eq!(conflicts[0].language, "en");
+        assert_eq!(conflicts[0].position, 1);
+        assert_eq!(
+            conflicts[0].types,
+            vec![
+                (PluralVariate::One, ArgumentType::Integer),
+                (PluralVariate::Other, ArgumentType::Object)
+            ]
+        );
+
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+
+        assert!(parse_from_bytes(raw)
+            .unwrap()
+            .localizable
+            .plural_type_conflicts()
+            .is_empty());
+    }
+
+    #[test]
+    fn source_language_only() {
+        let dir = std::env::temp_dir().join("localizable_source_language_only");
+
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+        let localized = parse_from_bytes(raw)
+            .unwrap()
+            .localizable
+            .localized_per_language()
+            .localized_for_android(AndroidLocalizeConfig {
+                source_language_only: true,
+                write_config: Some(AndroidWriteConfig {
+                    write_in: dir.clone(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert_eq!(
+            localized.sorted_languages.keys().collect::<Vec<_>>(),
+            vec!["en"]
+        );
+
+        let directories: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+
+        assert_eq!(directories, vec!["values"]);
+    }
+
+    #[test]
+    fn to_xcstrings_canonical_plurals() {
+        use crate::types::output::Translation;
+
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+        let localizable = parse_from_bytes(raw).unwrap().localizable;
+        let mut reversed = localizable.clone();
+
+        for st in &mut reversed.single_translation {
+            for translation in st.localization_value.language_translation.values_mut() {
+                if let Translation::PluralVariation(pv) = translation {
+                    pv.reverse();
+                }
+            }
+        }
+
+        let json = localizable.to_xcstrings();
+
+        assert_eq!(json, reversed.to_xcstrings());
+
+        let reparsed = parse_from_string(json).unwrap().localizable;
+
+        assert!(localizable.diff(&reparsed).is_empty());
+
+        reversed.canonicalize_plurals();
+
+        assert!(localizable.diff(&reversed).is_empty());
+
+        let raw = r#"{ "sourceLanguage" : "en", "strings" : {}, "version" : "1.1" }"#;
+        let json = parse_from_string(raw.to_string())
+            .unwrap()
+            .localizable
+            .to_xcstrings();
+
+        assert_eq!(parse_from_string(json).unwrap().localizable.version, "1.1");
+    }
+
+    #[test]
+    fn run_with_config() {
+        let dir = std::env::temp_dir().join("localizable_run_with_config");
+
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let source_file = current_dir()
+            .unwrap()
+            .join("test_resources")
+            .join("Localizable.xcstrings");
+        let raw = serde_json::json!({
+            "source_file": source_file,
+            "output_dir": dir,
+            "app_name": "Riddles",
+            "languages": ["nl"],
+            "xml_declaration": true,
+            "whitespace": "escape",
+            "directory_casing": "verbatim"
+        })
+        .to_string();
+        let config = crate::ConversionConfig::from_json(&raw).unwrap();
+
+        assert_eq!(config.whitespace, WhitespaceHandling::Escape);
+        assert_eq!(config.directory_casing, DirectoryCasing::Verbatim);
+
+        let localized = crate::run_with_config(&config).unwrap();
+
+        assert_eq!(
+            localized.sorted_languages.keys().collect::<Vec<_>>(),
+            vec!["nl"]
+        );
+
+        let content =
+            std::fs::read_to_string(dir.join("values-nl").join("localizable.xml")).unwrap();
+
+        assert!(content.starts_with("<?xml"));
+        assert!(content.contains("<string name=\"app_name\">Riddles</string>"));
+        assert!(matches!(
+            crate::ConversionConfig::from_json("{\"whitespace\": \"nope\"}"),
+            Err(ParsedError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn oversized_translations() {
+        let long = "a".repeat(crate::validate::DEFAULT_MAX_TRANSLATION_CHARS + 1);
+        let raw = format!(
+            r#"{{
+  "sourceLanguage" : "en",
+  "strings" : {{
+    "short" : {{ "localizations" : {{ "en" : {{ "stringUnit" : {{ "state" : "translated", "value" : "Short" }} }} }} }},
+    "long" : {{ "localizations" : {{
+      "en" : {{ "stringUnit" : {{ "state" : "translated", "value" : "Long" }} }},
+      "nl" : {{ "stringUnit" : {{ "state" : "translated", "value" : "{long}" }} }}
+    }} }}
+  }},
+  "version" : "1.0"
+}}"#
+        );
+        let localizable = parse_from_string(raw).unwrap().localizable;
+        let oversized =
+            localizable.oversized_translations(crate::validate::DEFAULT_MAX_TRANSLATION_CHARS);
+
+        assert_eq!(
+            oversized,
+            vec![crate::validate::OversizedTranslation {
+                key_raw: "long".to_string(),
+                language: "nl".to_string(),
+                char_count: crate::validate::DEFAULT_MAX_TRANSLATION_CHARS + 1,
+            }]
+        );
+        assert_eq!(localizable.oversized_translations(4).len(), 2);
+    }
+
+    #[test]
+    fn rename_language() {
+        use crate::types::output::RenameLanguageError;
+
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+        let original = parse_from_bytes(raw).unwrap().localizable;
+        let mut localizable = original.clone();
+
+        assert_eq!(
+            localizable.rename_language("nl", "en"),
+            Err(RenameLanguageError::LanguageExists("en".to_string()))
+        );
+        assert_eq!(
+            localizable.rename_language("iw", "he"),
+            Err(RenameLanguageError::UnknownLanguage("iw".to_string()))
+        );
+
+        localizable.rename_language("nl", "nl-BE").unwrap();
+
+        assert!(!localizable.languages().contains("nl"));
+
+        for (renamed, st) in localizable
+            .single_translation
+            .iter()
+            .zip(&original.single_translation)
+        {
+            assert_eq!(st.translation_for("nl"), renamed.translation_for("nl-BE"));
+        }
+
+        localizable.rename_language("en", "en-GB").unwrap();
+
+        assert_eq!(localizable.source_language, "en-GB");
+    }
+
+    #[test]
+    fn strict_states() {
+        let raw = include_bytes!("../test_resources/MisspelledState.xcstrings");
+
+        assert!(parse_from_bytes(raw).is_ok());
+
+        let error = parse_from_bytes_with_config(
+            raw,
+            &ParseConfig {
+                strict_states: true,
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+
+        assert!(matches!(error, ParsedError::UnknownState(state) if state == "translatd"));
+
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "tap" : { "localizations" : { "en" : { "variations" : { "device" : {
+      "iphone" : { "stringUnit" : { "state" : "translated", "value" : "Tap" } },
+      "mac" : { "stringUnit" : { "state" : "tranzlated", "value" : "Click" } }
+    } } } } }
+  },
+  "version" : "1.0"
+}"#;
+        let error = parse_from_bytes_with_config(
+            raw.as_bytes(),
+            &ParseConfig {
+                strict_states: true,
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+
+        assert!(matches!(error, ParsedError::UnknownState(state) if state == "tranzlated"));
+
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "files" : { "localizations" : { "en" : {
+      "stringUnit" : { "state" : "translated", "value" : "%#@files@" },
+      "substitutions" : { "files" : { "argNum" : 1, "formatSpecifier" : "lld", "variations" : { "plural" : {
+        "one" : { "stringUnit" : { "state" : "translated", "value" : "%arg file" } },
+        "other" : { "stringUnit" : { "state" : "reviewd", "value" : "%arg files" } }
+      } } } }
+    } } }
+  },
+  "version" : "1.0"
+}"#;
+        let error = parse_from_bytes_with_config(
+            raw.as_bytes(),
+            &ParseConfig {
+                strict_states: true,
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+
+        assert!(matches!(error, ParsedError::UnknownState(state) if state == "reviewd"));
+
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+
+        assert!(parse_from_bytes_with_config(
+            raw,
+            &ParseConfig {
+                strict_states: true,
+                ..Default::default()
+            },
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn source_flat_map() {
+        let raw = include_bytes!("../test_resources/MixedKinds.xcstrings");
+        let flat = parse_from_bytes(raw).unwrap().localizable.source_flat_map();
+
+        assert_eq!(
+            flat,
+            BTreeMap::from([
+                ("apples".to_string(), "%lld apples".to_string()),
+                ("title".to_string(), "Title".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn transformed_keys() {
+        assert!(crate::android::is_resource_name("already_valid_1"));
+        assert!(!crate::android::is_resource_name("Not valid"));
+        assert!(!crate::android::is_resource_name("double__underscore"));
+        assert_eq!(
+            crate::android::resource_name("double__underscore"),
+            "double_underscore"
+        );
+
+        let raw = include_bytes!("../test_resources/MixedKinds.xcstrings");
+        let mut localizable = parse_from_bytes(raw).unwrap().localizable;
+
+        assert!(localizable.transformed_keys().is_empty());
+
+        localizable.single_translation[0].key_raw = "Apples count".to_string();
+        localizable.single_translation[0].key_alphanumeric =
+            crate::android::resource_name("Apples count");
+
+        assert!(localizable.single_translation[0].is_key_transformed());
+        assert!(!localizable.single_translation[1].is_key_transformed());
+        assert_eq!(localizable.transformed_keys(), vec!["Apples count"]);
+    }
+
+    #[test]
+    fn state_transitions() {
+        let catalog = |title: &str, subtitle: &str| {
+            parse_from_string(format!(
+                r#"{{
+  "sourceLanguage" : "en",
+  "strings" : {{
+    "title" : {{ "localizations" : {{
+      "en" : {{ "stringUnit" : {{ "state" : "translated", "value" : "Title" }} }},
+      "nl" : {{ "stringUnit" : {{ "state" : "{title}", "value" : "Titel" }} }}
+    }} }},
+    "subtitle" : {{ "localizations" : {{
+      "en" : {{ "stringUnit" : {{ "state" : "translated", "value" : "Subtitle" }} }},
+      "nl" : {{ "stringUnit" : {{ "state" : "{subtitle}", "value" : "Ondertitel" }} }}
+    }} }}
+  }},
+  "version" : "1.0"
+}}"#
+            ))
+            .unwrap()
+            .localizable
+        };
+        let old = catalog("new", "translated");
+        let new = catalog("translated", "needs_review");
+        let report = old.state_transitions(&new);
+
+        assert_eq!(report.count("nl", State::New, State::Translated), 1);
+        assert_eq!(report.count("nl", State::Translated, State::NeedsReview), 1);
+        assert_eq!(report.count("en", State::New, State::Translated), 0);
+        assert_eq!(report.transitions["nl"].len(), 2);
+        assert!(report
+            .to_markdown()
+            .contains("| nl | new | translated | 1 |"));
+        assert!(old.state_transitions(&old).transitions.is_empty());
+    }
+
+    #[test]
+    fn capitalization_mismatches() {
+        let raw = include_bytes!("../test_resources/Capitalization.xcstrings");
+        let mismatches = parse_from_bytes(raw)
+            .unwrap()
+            .localizable
+            .capitalization_mismatches(crate::validate::DEFAULT_CASELESS_LANGUAGES);
+
+        assert_eq!(
+            mismatches,
+            vec![crate::validate::CapitalizationMismatch {
+                key_raw: "settings".to_string(),
+                language: "de".to_string(),
+                source_uppercase: true,
+            }]
+        );
+
+        let raw = r#"{
+            "sourceLanguage": "en",
+            "strings": {
+                "settings": { "localizations": {
+                    "en": { "stringUnit": { "state": "translated", "value": "Settings" } },
+                    "ja-JP": { "stringUnit": { "state": "translated", "value": "settings 設定" } },
+                    "zh-Hans-CN": { "stringUnit": { "state": "translated", "value": "settings 设置" } },
+                    "nl-BE": { "stringUnit": { "state": "translated", "value": "instellingen" } }
+                } }
+            },
+            "version": "1.0"
+        }"#;
+        let mismatches = parse_from_string(raw.to_string())
+            .unwrap()
+            .localizable
+            .capitalization_mismatches(crate::validate::DEFAULT_CASELESS_LANGUAGES);
+
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].language, "nl-BE");
+    }
+
+    #[test]
+    fn plural_matrix() {
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+        let mut localizable = parse_from_bytes(raw).unwrap().localizable;
+        let single_translation = localizable
+            .single_translation
+            .iter_mut()
+            .find(|st| st.key_raw == "amount_favorite_riddles")
+            .unwrap();
+
+        if let Some(crate::types::output::Translation::PluralVariation(pv)) = single_translation
+            .localization_value
+            .language_translation
+            .get_mut("nl")
+        {
+            pv.retain(|single| single.variate != PluralVariate::One);
+        }
+
+        let matrix = single_translation.plural_matrix();
+
+        assert_eq!(matrix.keys().collect::<Vec<_>>(), vec!["en", "nl"]);
+        assert_eq!(
+            matrix["en"]
+                .iter()
+                .map(|(variate, value)| (variate.clone(), value.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (PluralVariate::One, "%1$lld favorite riddle"),
+                (PluralVariate::Other, "%1$lld favorite riddles")
+            ]
+        );
+        assert_eq!(matrix["nl"][&PluralVariate::One], "");
+        assert_eq!(
+            matrix["nl"][&PluralVariate::Other],
+            "%1$lld favoriete raadsels"
+        );
+    }
+
+    #[cfg(feature = "zip")]
+    #[test]
+    fn to_zip() {
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+        let localizable = parse_from_bytes(raw).unwrap().localizable;
+        let localized = localizable
+            .localized_per_language()
+            .localized_for_android(Default::default())
+            .unwrap();
+        let file_name = AndroidWriteConfig {
+            table_name: Some("Localizable".to_string()),
+            ..Default::default()
+        }
+        .file_name()
+        .unwrap();
+        let entries = localized.entries(
+            &localizable.source_language,
+            DirectoryCasing::Android,
+            &file_name,
+        );
+        let names: Vec<_> = entries.iter().map(|(name, _)| name.as_str()).collect();
+
+        assert!(names.contains(&"values/localizable.xml"));
+        assert!(names.contains(&"values-nl/localizable.xml"));
+
+        let zip = localized
+            .to_zip(
+                &localizable.source_language,
+                DirectoryCasing::Android,
+                &file_name,
+            )
+            .unwrap();
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(zip)).unwrap();
+
+        assert_eq!(archive.file_names().count(), entries.len());
+
+        for (name, data) in &entries {
+            let mut file = archive.by_name(name).unwrap();
+            let mut content = vec![];
+
+            std::io::Read::read_to_end(&mut file, &mut content).unwrap();
+
+            assert_eq!(file.compression(), zip::CompressionMethod::Stored);
+            assert_eq!(&content, data);
+        }
+    }
+
+    #[test]
+    fn duplicate_comments() {
+        let raw = include_bytes!("../test_resources/Comments.xcstrings");
+        let mut localizable = parse_from_bytes(raw).unwrap().localizable;
+
+        assert_eq!(
+            localizable.duplicate_comments(),
+            vec![(
+                "Title of a screen".to_string(),
+                vec!["profile_title".to_string(), "settings_title".to_string()]
+            )]
+        );
+
+        // `cancel` has no comment, an empty comment shared with it isn't reported
+        localizable.single_translation[2].comment = String::new();
+
+        assert_eq!(localizable.duplicate_comments().len(), 1);
+    }
+
+    #[test]
+    fn parse_lenient() {
+        let raw = include_str!("../test_resources/Malformed.xcstrings");
+
+        assert!(parse_from_string(raw.to_string()).is_err());
+
+        let lenient = parse_from_string_lenient(raw.to_string(), &ParseConfig::default()).unwrap();
+
+        assert_eq!(
+            lenient
+                .skipped
+                .iter()
+                .map(|(key, _)| key.as_str())
+                .collect::<Vec<_>>(),
+            vec!["broken"]
+        );
+        assert_eq!(lenient.parsed.localizable.single_translation.len(), 1);
+        assert_eq!(
+            lenient.parsed.localizable.single_translation[0].key_raw,
+            "title"
+        );
+        assert!(parse_from_string_lenient("[".to_string(), &ParseConfig::default()).is_err());
+    }
+
+    #[test]
+    fn character_count() {
+        let raw = include_bytes!("../test_resources/Capitalization.xcstrings");
+        let localized = parse_from_bytes(raw)
+            .unwrap()
+            .localizable
+            .localized_per_language();
+
+        // Settings + kg
+        assert_eq!(localized.character_count("en"), 10);
+        assert_eq!(localized.character_count("ja"), 2);
+        assert_eq!(localized.character_count("fr"), 0);
+
+        let raw = include_bytes!("../test_resources/MixedKinds.xcstrings");
+        let localized = parse_from_bytes(raw)
+            .unwrap()
+            .localizable
+            .localized_per_language();
+
+        // %lld apple + %lld apples + Title
+        assert_eq!(localized.character_count("en"), 10 + 11 + 5);
+    }
+
+    #[test]
+    fn cdata_markup() {
+        use crate::types::inoutoutput::TranslationValue;
+
+        let value = |value: &str| TranslationValue {
+            state: crate::TRANSLATED_STATE.to_string(),
+            value: value.to_string(),
+        };
+        let config = AndroidLocalizeConfig {
+            cdata_markup: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config.sanitize(&value("<b>%@</b> isn't here")),
+            "<![CDATA[<b>%s</b> isn\\'t here]]>"
+        );
+        assert_eq!(
+            config.sanitize(&value("<i>a]]>b</i>")),
+            "<![CDATA[<i>a]]]]><![CDATA[>b</i>]]>"
+        );
+        assert_eq!(config.sanitize(&value("a b")), "a b");
+        assert_eq!(
+            AndroidLocalizeConfig::default().sanitize(&value("<b>bold</b>")),
+            "<b>bold</b>"
+        );
+    }
+
+    #[test]
+    fn languages_by_completion() {
+        let raw = include_bytes!("../test_resources/Capitalization.xcstrings");
+        let languages = parse_from_bytes(raw)
+            .unwrap()
+            .localizable
+            .localized_per_language()
+            .languages_by_completion();
+
+        assert_eq!(
+            languages,
+            vec![
+                ("de".to_string(), 100.0),
+                ("en".to_string(), 100.0),
+                ("ja".to_string(), 50.0),
+                ("nl".to_string(), 50.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn add_key() {
+        use crate::types::output::AddKeyError;
+
+        let raw = include_bytes!("../test_resources/Comments.xcstrings");
+        let mut localizable = parse_from_bytes(raw).unwrap().localizable;
+
+        localizable
+            .add_key("delete", "Delete", "Shown on the delete button")
+            .unwrap();
+
+        let keys: Vec<_> = localizable
+            .single_translation
+            .iter()
+            .map(|st| st.key_raw.as_str())
+            .collect();
+
+        assert_eq!(
+            keys,
+            vec![
+                "cancel",
+                "delete",
+                "profile_title",
+                "save",
+                "settings_title"
+            ]
+        );
+
+        let added = &localizable.single_translation[1];
+
+        assert_eq!(added.comment, "Shown on the delete button");
+        assert_eq!(
+            added.translation_for("en").unwrap().display_value(),
+            "Delete"
+        );
+        assert!(added.translation_for("en").unwrap().is_translated());
+
+        assert_eq!(
+            localizable.add_key(" padded", "Padded", ""),
+            Err(AddKeyError::InvalidKey(" padded".to_string()))
+        );
+        assert_eq!(
+            localizable.add_key("save", "Save", ""),
+            Err(AddKeyError::KeyExists("save".to_string()))
+        );
+        assert_eq!(
+            localizable.add_key("Profile title", "Profile", ""),
+            Err(AddKeyError::AndroidNameCollision {
+                key_raw: "Profile title".to_string(),
+                existing_key_raw: "profile_title".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn to_info_plist_strings() {
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "CFBundleDisplayName" : { "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Riddles" } },
+      "nl" : { "stringUnit" : { "state" : "translated", "value" : "Raadsels" } }
+    } },
+    "NSCameraUsageDescription" : { "comment" : "Camera permission", "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Scan a \"riddle\"" } }
+    } },
+    "title" : { "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Title" } }
+    } }
+  },
+  "version" : "1.0"
+}"#;
+        let files = parse_from_string(raw.to_string())
+            .unwrap()
+            .localizable
+            .to_info_plist_strings(&["CFBundleDisplayName", "NSCameraUsageDescription"]);
+
+        assert_eq!( files["en"], "\"CFBundleDisplayName\" = \"Riddles\";\n\n/* Camera permission */\n\"NSCameraUsageDescription\" = \"Scan a \\\"riddle\\\"\";");
+        assert_eq!(files["nl"], "\"CFBundleDisplayName\" = \"Raadsels\";");
+    }
+
+    #[test]
+    fn duplicate_source_values() {
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "ok" : { "localizations" : { "en" : { "stringUnit" : { "state" : "translated", "value" : "OK" } } } },
+    "ok_button" : { "localizations" : { "en" : { "stringUnit" : { "state" : "translated", "value" : "OK" } } } },
+    "save" : { "localizations" : { "en" : { "stringUnit" : { "state" : "translated", "value" : "Save changes" } } } },
+    "save_button" : { "localizations" : { "en" : { "stringUnit" : { "state" : "translated", "value" : "Save changes" } } } },
+    "title" : { "localizations" : { "en" : { "stringUnit" : { "state" : "translated", "value" : "Title" } } } }
+  },
+  "version" : "1.0"
+}"#;
+        let localizable = parse_from_string(raw.to_string()).unwrap().localizable;
+
+        assert_eq!(
+            localizable.duplicate_source_values(3),
+            BTreeMap::from([(
+                "Save changes".to_string(),
+                vec!["save".to_string(), "save_button".to_string()]
+            )])
+        );
+        assert_eq!(localizable.duplicate_source_values(0).len(), 2);
+    }
+
+    #[test]
+    fn reverse_android_keys_source() {
+        use crate::android::SourceLanguage;
+
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "Price: $%@" : { "localizations" : { "en" : { "stringUnit" : { "state" : "translated", "value" : "Price: $%@" } } } }
+  },
+  "version" : "1.0"
+}"#;
+        let localizable = parse_from_string(raw.to_string()).unwrap().localizable;
+
+        assert_eq!(
+            localizable.reverse_android_keys_source(SourceLanguage::Rust, "ANDROID_KEYS"),
+            "pub const ANDROID_KEYS: &[(&str, &str)] = &[\n    (\"price\", \"Price: $%@\"),\n];\n"
+        );
+        assert!(localizable
+            .reverse_android_keys_source(SourceLanguage::Kotlin, "AndroidKeys")
+            .contains("        \"price\" to \"Price: \\$%@\","));
+    }
+
+    #[test]
+    fn fill_missing_plural_from_other() {
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "days" : { "localizations" : {
+      "en" : { "variations" : { "plural" : {
+        "one" : { "stringUnit" : { "state" : "translated", "value" : "%lld day" } },
+        "other" : { "stringUnit" : { "state" : "translated", "value" : "%lld days" } }
+      } } },
+      "nl" : { "variations" : { "plural" : {
+        "other" : { "stringUnit" : { "state" : "translated", "value" : "%lld dagen" } }
+      } } }
+    } }
+  },
+  "version" : "1.0"
+}"#;
+        let localized_per_language = parse_from_string(raw.to_string())
+            .unwrap()
+            .localizable
+            .localized_per_language();
+        let nl = |fill_missing_plural_from_other| {
+            localized_per_language
+                .localized_for_android(AndroidLocalizeConfig {
+                    fill_missing_plural_from_other,
+                    ..Default::default()
+                })
+                .unwrap()
+                .sorted_languages
+                .remove("nl")
+                .unwrap()
+        };
+
+        assert!(!nl(false).contains("quantity=\"one\""));
+        assert!(nl(true).contains(
+            "<item quantity=\"one\">%d dagen</item>\n<item quantity=\"other\">%d dagen</item>"
+        ));
+        assert_eq!(
+            PluralVariate::required_for("ru-RU"),
+            &[
+                PluralVariate::One,
+                PluralVariate::Few,
+                PluralVariate::Many,
+                PluralVariate::Other
+            ]
+        );
+    }
+
+    #[test]
+    fn is_roundtrip_stable() {
+        use crate::diff::RoundtripError;
+
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+        let mut parsed = parse_from_bytes(raw).unwrap();
+
+        assert!(parsed.is_roundtrip_stable(&Default::default()).is_ok());
+
+        // Saved without a source value, parsing falls back to the key
+        let key_raw = parsed.localizable.single_translation[4].key_raw.to_string();
+
+        parsed.localizable.single_translation[4]
+            .localization_value
+            .language_translation
+            .remove("en");
+
+        let Err(RoundtripError::Changed(diff)) = parsed.is_roundtrip_stable(&Default::default())
+        else {
+            panic!("Expected a difference");
+        };
+
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].key_raw, key_raw);
+        assert_eq!(diff.changed[0].language, "en");
+
+        let custom = r#"{
+            "sourceLanguage": "en",
+            "strings": {
+                "hello": { "localizations": {
+                    "en": { "stringUnit": { "state": "done", "value": "Hello" } }
+                } }
+            },
+            "version": "1.0"
+        }"#;
+        let config = ParseConfig {
+            state_names: StateNames {
+                translated: "done".to_string(),
+                ..Default::default()
+            },
+            strict_states: true,
+            ..Default::default()
+        };
+        let parsed = parse_from_string_with_config(custom.to_string(), &config).unwrap();
+
+        assert!(parsed.is_roundtrip_stable(&config).is_ok());
+    }
+
+    #[test]
+    fn header_comment() {
+        let raw = include_bytes!("../test_resources/MixedKinds.xcstrings");
+        let localized = parse_from_bytes(raw)
+            .unwrap()
+            .localizable
+            .localized_per_language()
+            .localized_for_android(AndroidLocalizeConfig {
+                xml_declaration: true,
+                header_comment: Some("DO NOT EDIT -- generated --> from xcstrings".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert!(localized.sorted_languages["nl"].starts_with(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<!-- DO NOT EDIT - - generated - -> from xcstrings -->\n<resources>"
+        ));
+    }
+
+    #[test]
+    fn raw_key_in_translation() {
+        let raw = include_bytes!("../test_resources/KeyInValue.xcstrings");
+
+        assert_eq!(
+            parse_from_bytes(raw)
+                .unwrap()
+                .localizable
+                .raw_key_in_translation(),
+            vec![("welcome_message".to_string(), "nl".to_string())]
+        );
+    }
+
+    #[test]
+    fn to_arb() {
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "braces" : { "comment" : "Literal braces", "localizations" : { "en" : { "stringUnit" : { "state" : "translated", "value" : "Use {name} for %@'s name, 100%%" } } } },
+    "items" : { "localizations" : { "en" : { "variations" : { "plural" : {
+      "one" : { "stringUnit" : { "state" : "translated", "value" : "%lld item #1" } },
+      "other" : { "stringUnit" : { "state" : "translated", "value" : "%lld items" } }
+    } } } } }
+  },
+  "version" : "1.0"
+}"#;
+        let localizable = parse_from_string(raw.to_string()).unwrap().localizable;
+        let arb: serde_json::Value = serde_json::from_str(&localizable.to_arb("en", true)).unwrap();
+
+        assert_eq!(arb["@@locale"], "en");
+        assert_eq!(arb["braces"], "Use '{'name'}' for {arg1}''s name, 100%");
+        assert_eq!(arb["@braces"]["description"], "Literal braces");
+        assert!(arb["@braces"]["placeholders"]["arg1"].is_object());
+        assert_eq!(
+            arb["items"],
+            "{arg1, plural, one{{arg1} item '#'1} other{{arg1} items}}"
+        );
+
+        let arb: serde_json::Value =
+            serde_json::from_str(&localizable.to_arb("en", false)).unwrap();
+
+        assert_eq!(arb["braces"], "Use {name} for {arg1}'s name, 100%");
+
+        let raw = include_bytes!("../test_resources/Substitutions.xcstrings");
+        let arb: serde_json::Value = serde_json::from_str(
+            &parse_from_bytes(raw)
+                .unwrap()
+                .localizable
+                .to_arb("en", true),
+        )
+        .unwrap();
+
+        assert_eq!(
+            arb["files_in_folders"],
+            "Found {arg1, plural, one{{arg1} file} other{{arg1} files}} in \
+             {arg2, plural, one{{arg2} folder} other{{arg2} folders}}"
+        );
+        assert!(arb["@files_in_folders"]["placeholders"]["arg1"].is_object());
+        assert!(arb["@files_in_folders"]["placeholders"]["arg2"].is_object());
+
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "unknown" : { "localizations" : { "en" : { "stringUnit" : { "state" : "translated", "value" : "Found %#@files@" } } } }
+  },
+  "version" : "1.0"
+}"#;
+        let arb: serde_json::Value = serde_json::from_str(
+            &parse_from_string(raw.to_string())
+                .unwrap()
+                .localizable
+                .to_arb("en", true),
+        )
+        .unwrap();
+
+        assert_eq!(arb["unknown"], "Found {files}");
+        assert!(arb["@unknown"]["placeholders"]["files"].is_object());
+        assert!(arb["@unknown"]["placeholders"]["arg1"].is_null());
+    }
+
+    #[test]
+    fn trailing_punctuation_mismatches() {
+        let raw = include_bytes!("../test_resources/TrailingPunctuation.xcstrings");
+
+        assert_eq!(
+            parse_from_bytes(raw)
+                .unwrap()
+                .localizable
+                .trailing_punctuation_mismatches(crate::validate::DEFAULT_TRAILING_PUNCTUATION),
+            vec![crate::validate::TrailingPunctuationMismatch {
+                key_raw: "saved".to_string(),
+                language: "nl".to_string(),
+                source: Some('.'),
+                translation: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn max_argument_index() {
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "greeting" : { "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Hi %1$@, it's %2$@" } },
+      "nl" : { "stringUnit" : { "state" : "translated", "value" : "Het is %2$@, %1$@" } }
+    } },
+    "sequential" : { "localizations" : {
+      "en" : { "variations" : { "plural" : {
+        "one" : { "stringUnit" : { "state" : "translated", "value" : "%lld item" } },
+        "other" : { "stringUnit" : { "state" : "translated", "value" : "%lld items in %@" } }
+      } } }
+    } },
+    "title" : { "localizations" : { "en" : { "stringUnit" : { "state" : "translated", "value" : "Title" } } } },
+    "substituted" : { "localizations" : { "en" : { "stringUnit" : { "state" : "translated", "value" : "%#@files@ in %@" } } } }
+  },
+  "version" : "1.0"
+}"#;
+        let localizable = parse_from_string(raw.to_string()).unwrap().localizable;
+        let index = localizable.build_index();
+
+        assert_eq!(index.get("greeting").unwrap().max_argument_index(), 2);
+        assert_eq!(index.get("sequential").unwrap().max_argument_index(), 2);
+        assert_eq!(index.get("title").unwrap().max_argument_index(), 0);
+        assert_eq!(index.get("substituted").unwrap().max_argument_index(), 1);
+        assert_eq!(
+            crate::specifier::specifiers("%#@files@ in %1$#@folders@")
+                .into_iter()
+                .map(|specifier| specifier.raw)
+                .collect::<Vec<_>>(),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn to_icu_bundle() {
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+        let bundle: serde_json::Value = serde_json::from_str(
+            &parse_from_bytes(raw)
+                .unwrap()
+                .localizable
+                .to_icu_bundle(true),
+        )
+        .unwrap();
+
+        assert_eq!(
+            bundle["en"]["amount_favorite_riddles"],
+            "{arg1, plural, one{{arg1} favorite riddle} other{{arg1} favorite riddles}}"
+        );
+        assert_eq!(
+            bundle["nl"]["amount_favorite_riddles"],
+            "{arg1, plural, one{{arg1} favoriet raadsel} other{{arg1} favoriete raadsels}}"
+        );
+    }
+
+    #[test]
+    fn whitespace_only_value_variants() {
+        let raw = include_bytes!("../test_resources/WhitespaceVariants.xcstrings");
+
+        assert_eq!(
+            parse_from_bytes(raw)
+                .unwrap()
+                .localizable
+                .whitespace_only_value_variants(),
+            BTreeMap::from([(
+                "Continue".to_string(),
+                vec!["continue".to_string(), "continue_button".to_string()]
+            )])
+        );
+    }
+
+    #[test]
+    fn plural_order() {
+        use crate::types::output::{PluralOrder, Translation};
+
+        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
+        let mut localizable = parse_from_bytes(raw).unwrap().localizable;
+        let single_translation = localizable
+            .single_translation
+            .iter_mut()
+            .find(|st| st.key_raw == "amount_favorite_riddles")
+            .unwrap();
+
+        // A plural built programmatically in a non-canonical order
+        if let Some(Translation::PluralVariation(pv)) = single_translation
+            .localization_value
+            .language_translation
+            .get_mut("nl")
+        {
+            let mut few = pv[0].clone();
+
+            few.variate = PluralVariate::Few;
+            pv.reverse();
+            pv.insert(1, few);
+        }
+
+        let nl = |plural_order| {
+            let xml = localizable
+                .localized_per_language()
+                .localized_for_android(AndroidLocalizeConfig {
+                    plural_order,
+                    ..Default::default()
+                })
+                .unwrap()
+                .sorted_languages
+                .remove("nl")
+                .unwrap();
+            let start = xml.find("<plurals").unwrap();
+            let end = xml.find("</plurals>").unwrap();
+
+            xml[start..end]
+                .lines()
+                .filter_map(|line| line.split('"').nth(1).filter(|_| line.starts_with("<item")))
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(nl(PluralOrder::Canonical), vec!["one", "few", "other"]);
+        assert_eq!(nl(PluralOrder::default()), vec!["one", "few", "other"]);
+        assert_eq!(nl(PluralOrder::OtherLast), vec!["few", "one", "other"]);
+    }
+
+    #[test]
+    fn conversion_report() {
+        use crate::types::output::{ConversionWarning, SkipReason, SkippedEntry, WarningKind};
+
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "brand" : { "shouldTranslate" : false, "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Riddles" } },
+      "nl" : { "stringUnit" : { "state" : "translated", "value" : "Raadsels" } }
+    } },
+    "items" : { "localizations" : {
+      "en" : { "variations" : { "plural" : {
+        "one" : { "stringUnit" : { "state" : "translated", "value" : "%lld item" } },
+        "other" : { "stringUnit" : { "state" : "translated", "value" : "%lld items" } }
+      } } },
+      "nl" : { "stringUnit" : { "state" : "translated", "value" : "%lld dingen" } }
+    } },
+    "title" : { "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Title" } },
+      "nl" : { "stringUnit" : { "state" : "new", "value" : "Titel" } }
+    } },
+    "welcome" : { "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Welcome %@" } },
+      "nl" : { "stringUnit" : { "state" : "translated", "value" : "Welkom" } }
+    } }
+  },
+  "version" : "1.0"
+}"#;
+        let parsed = parse_from_string(raw.to_string()).unwrap();
+        let (localized, report) = parsed
+            .localizable
+            .localized_for_android_with_report(Default::default())
+            .unwrap();
+
+        assert_eq!(report.languages_written, vec!["en", "nl"]);
+        assert_eq!(
+            report.skipped,
+            vec![
+                SkippedEntry {
+                    key_raw: "brand".to_string(),
+                    language: "en".to_string(),
+                    reason: SkipReason::NonTranslatable,
+                },
+                SkippedEntry {
+                    key_raw: "brand".to_string(),
+                    language: "nl".to_string(),
+                    reason: SkipReason::NonTranslatable,
+                },
+                SkippedEntry {
+                    key_raw: "title".to_string(),
+                    language: "nl".to_string(),
+                    reason: SkipReason::NewState,
+                }
+            ]
+        );
+        assert_eq!(
+            report.warnings,
+            vec![
+                ConversionWarning {
+                    key_raw: "welcome".to_string(),
+                    language: "nl".to_string(),
+                    kind: WarningKind::PositionMismatch {
+                        expected: [1].into(),
+                        actual: [].into(),
+                    },
+                },
+                ConversionWarning {
+                    key_raw: "items".to_string(),
+                    language: "nl".to_string(),
+                    kind: WarningKind::MixedKind,
+                }
+            ]
+        );
+        assert!(!localized.sorted_languages["nl"].contains("Titel"));
+        assert!(localized.sorted_languages["en"].contains("Title"));
+        assert!(!localized.sorted_languages["nl"].contains("Raadsels"));
+        assert!(!localized.sorted_languages["en"].contains("Riddles"));
+
+        let (localized, report) = parsed
+            .localizable
+            .localized_for_android_with_report(AndroidLocalizeConfig {
+                emit_non_translatable: true,
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert!(!report
+            .skipped
+            .iter()
+            .any(|skipped| skipped.key_raw == "brand" && skipped.language == "en"));
+        assert!(localized.sorted_languages["en"].contains("Riddles"));
+    }
+
+    #[test]
+    fn normalize_language_codes() {
+        use crate::types::output::{normalize_language_code, LanguageConflict};
+
+        assert_eq!(normalize_language_code("pt_br"), "pt-BR");
+        assert_eq!(normalize_language_code("ZH_hans_cn"), "zh-Hans-CN");
+        assert_eq!(normalize_language_code("es-419"), "es-419");
+
+        let catalog = |pt_br_value: &str| {
+            parse_from_string(format!(
+                r#"{{
+  "sourceLanguage" : "en",
+  "strings" : {{
+    "title" : {{ "localizations" : {{
+      "en" : {{ "stringUnit" : {{ "state" : "translated", "value" : "Title" }} }},
+      "pt-BR" : {{ "stringUnit" : {{ "state" : "translated", "value" : "Título" }} }}
+    }} }},
+    "save" : {{ "localizations" : {{
+      "en" : {{ "stringUnit" : {{ "state" : "translated", "value" : "Save" }} }},
+      "pt_br" : {{ "stringUnit" : {{ "state" : "translated", "value" : "Salvar" }} }}
+    }} }},
+    "other" : {{ "localizations" : {{
+      "en" : {{ "stringUnit" : {{ "state" : "translated", "value" : "Title" }} }},
+      "pt-BR" : {{ "stringUnit" : {{ "state" : "translated", "value" : "Título" }} }},
+      "pt_br" : {{ "stringUnit" : {{ "state" : "translated", "value" : "{pt_br_value}" }} }}
+    }} }}
+  }},
+  "version" : "1.0"
+}}"#
+            ))
+            .unwrap()
+            .localizable
+        };
+
+        let mut localizable = catalog("Título");
+
+        localizable.normalize_language_codes().unwrap();
+
+        assert_eq!(
+            localizable.languages().into_iter().collect::<Vec<_>>(),
+            vec!["en", "pt-BR"]
+        );
+        assert_eq!(
+            localizable
+                .build_index()
+                .get("save")
+                .unwrap()
+                .translation_for("pt-BR")
+                .unwrap()
+                .display_value(),
+            "Salvar"
+        );
+
+        let mut conflicting = catalog("Outro");
+
+        assert_eq!(
+            conflicting.normalize_language_codes(),
+            Err(LanguageConflict {
+                key_raw: "other".to_string(),
+                language: "pt-BR".to_string(),
+            })
+        );
+        assert!(conflicting.languages().contains("pt_br"));
+    }
+
     #[test]
-    fn update_android_xmls() {
-        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
-        let current = current_dir().unwrap().join("test_resources");
-        let _ = parse_from_bytes(raw)
+    fn placeholder_spec() {
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "greeting" : { "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "It's %2$lld o'clock, %1$@" } }
+    } },
+    "files" : { "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Found %#@files@" } }
+    } }
+  },
+  "version" : "1.0"
+}"#;
+        let spec: serde_json::Value = serde_json::from_str(
+            &parse_from_string(raw.to_string())
+                .unwrap()
+                .localizable
+                .placeholder_spec(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            spec["greeting"],
+            serde_json::json!({
+                "placeholders": [
+                    { "position": 1, "type": "object", "specifier": "%1$@" },
+                    { "position": 2, "type": "integer", "specifier": "%2$lld" }
+                ],
+                "substitutions": []
+            })
+        );
+        assert_eq!(spec["files"]["substitutions"], serde_json::json!(["files"]));
+        assert_eq!(spec["files"]["placeholders"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn validators_independent_of_conversion() {
+        use crate::types::inoutoutput::TranslationValue;
+        use crate::types::output::{Localizable, Translation};
+
+        // Built by hand, never parsed nor converted
+        let mut localizable = Localizable {
+            source_language: "en".to_string(),
+            single_translation: vec![],
+            version: "1.0".to_string(),
+        };
+
+        localizable
+            .add_key("invite", "%1$@ invited %2$@\u{7}", "")
+            .unwrap();
+        localizable.single_translation[0]
+            .localization_value
+            .language_translation
+            .insert(
+                "nl".to_string(),
+                Translation::Localization(TranslationValue {
+                    state: TRANSLATED_STATE.to_string(),
+                    value: "%2$@ is uitgenodigd".to_string(),
+                }),
+            );
+
+        let issues = localizable.positional_specifier_issues();
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].gaps(), vec![1]);
+        assert_eq!(localizable.control_characters().len(), 1);
+        assert_eq!(
+            issues,
+            localizable
+                .localized_per_language()
+                .positional_specifier_issues()
+        );
+    }
+
+    #[test]
+    fn generated_comment_prefix() {
+        let mut localizable =
+            parse_from_bytes(include_bytes!("../test_resources/Comments.xcstrings"))
+                .unwrap()
+                .localizable;
+
+        assert!(localizable.set_generated_comment(
+            "cancel",
+            "Cancel button",
+            crate::DEFAULT_AUTO_COMMENT_PREFIX
+        ));
+        assert!(!localizable.set_comment("missing", "Comment"));
+
+        let cancel = localizable
+            .single_translation
+            .iter()
+            .find(|st| st.key_raw == "cancel")
+            .unwrap();
+
+        assert_eq!(cancel.comment, "[auto] Cancel button");
+
+        let xcstrings = localizable.to_xcstrings();
+        let config = ParseConfig {
+            strip_comment_prefix: Some(crate::DEFAULT_AUTO_COMMENT_PREFIX.to_string()),
+            ..Default::default()
+        };
+        let reparsed = parse_from_bytes_with_config(xcstrings.as_bytes(), &config)
+            .unwrap()
+            .localizable;
+
+        let comments: Vec<_> = reparsed
+            .single_translation
+            .iter()
+            .map(|st| st.comment.as_str())
+            .collect();
+
+        assert_eq!(
+            comments,
+            vec![
+                "Cancel button",
+                "Title of a screen",
+                "Shown on the save button",
+                "Title of a screen"
+            ]
+        );
+    }
+
+    #[test]
+    fn typography_issues() {
+        use crate::validate::{TypographyChecks, TypographyIssue, TypographyIssueKind};
+
+        let localizable =
+            parse_from_bytes(include_bytes!("../test_resources/Typography.xcstrings"))
+                .unwrap()
+                .localizable;
+        let issue = |key_raw: &str, language: &str, kind| TypographyIssue {
+            key_raw: key_raw.to_string(),
+            language: language.to_string(),
+            kind,
+        };
+
+        assert_eq!(
+            localizable.typography_issues(TypographyChecks::default()),
+            vec![
+                issue(
+                    "delete",
+                    "en",
+                    TypographyIssueKind::RepeatedWord("the".to_string())
+                ),
+                issue("delete", "nl", TypographyIssueKind::DoubleSpace),
+                issue(
+                    "that",
+                    "en",
+                    TypographyIssueKind::RepeatedWord("that".to_string())
+                ),
+                issue(
+                    "title",
+                    "nl",
+                    TypographyIssueKind::RepeatedWord("Bestanden".to_string())
+                ),
+            ]
+        );
+        assert_eq!(
+            localizable.typography_issues(TypographyChecks {
+                repeated_words: false,
+                double_spaces: true,
+            }),
+            vec![issue("delete", "nl", TypographyIssueKind::DoubleSpace)]
+        );
+    }
+
+    #[test]
+    fn non_translatable_file() {
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "brand" : { "shouldTranslate" : false, "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Acme" } },
+      "nl" : { "stringUnit" : { "state" : "translated", "value" : "Acme" } }
+    } },
+    "title" : { "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Title" } },
+      "nl" : { "stringUnit" : { "state" : "translated", "value" : "Titel" } }
+    } }
+  },
+  "version" : "1.0"
+}"#;
+        let localizable = parse_from_string(raw.to_string()).unwrap().localizable;
+
+        assert!(!localizable.single_translation[0].should_translate);
+        assert!(localizable
+            .to_xcstrings()
+            .contains("\"shouldTranslate\": false"));
+
+        let localized = localizable
+            .localized_per_language()
+            .localized_for_android(AndroidLocalizeConfig {
+                non_translatable_file: Some("donottranslate".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert_eq!( localized.grouped_languages["en"]["donottranslate.xml"], "<resources>\n<string name=\"brand\" translatable=\"false\">Acme</string>\n</resources>");
+        assert!(!localized.grouped_languages.contains_key("nl"));
+
+        for xml in localized.sorted_languages.values() {
+            assert!(!xml.contains("brand"));
+        }
+
+        assert!(localized.sorted_languages["nl"].contains("Titel"));
+    }
+
+    #[test]
+    fn dedupe_references() {
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "cancel" : { "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Cancel" } }
+    } },
+    "dialog_cancel" : { "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Cancel" } }
+    } },
+    "items" : { "localizations" : {
+      "en" : { "variations" : { "plural" : {
+        "one" : { "stringUnit" : { "state" : "translated", "value" : "Cancel" } },
+        "other" : { "stringUnit" : { "state" : "translated", "value" : "Cancel" } }
+      } } }
+    } }
+  },
+  "version" : "1.0"
+}"#;
+        let localized = parse_from_string(raw.to_string())
+            .unwrap()
+            .localizable
+            .localized_per_language()
+            .localized_for_android(AndroidLocalizeConfig {
+                dedupe_references: true,
+                ..Default::default()
+            })
+            .unwrap();
+        let xml = &localized.sorted_languages["en"];
+
+        assert!(xml.contains("<string name=\"cancel\">Cancel</string>"));
+        assert!(xml.contains("<string name=\"dialog_cancel\">@string/cancel</string>"));
+        assert!(xml.contains("<item quantity=\"one\">Cancel</item>"));
+    }
+
+    #[test]
+    fn distinct_placeholders() {
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "files" : { "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "%@ found %#@count@ (100%%)" } },
+      "nl" : { "stringUnit" : { "state" : "translated", "value" : "%#@count@ gevonden door %@" } }
+    } },
+    "roses" : { "localizations" : {
+      "en" : { "variations" : { "plural" : {
+        "one" : { "stringUnit" : { "state" : "translated", "value" : "%1$lld rose for %2$@" } },
+        "other" : { "stringUnit" : { "state" : "translated", "value" : "%1$lld roses for %2$@" } }
+      } } }
+    } }
+  },
+  "version" : "1.0"
+}"#;
+        let localizable = parse_from_string(raw.to_string()).unwrap().localizable;
+
+        assert_eq!(
+            localizable.distinct_placeholders(),
+            std::collections::BTreeSet::from(
+                ["%#@count@", "%1$lld", "%2$@", "%@"].map(String::from)
+            )
+        );
+    }
+
+    #[test]
+    fn empty_source_values() {
+        let localizable =
+            parse_from_bytes(include_bytes!("../test_resources/EmptySource.xcstrings"))
+                .unwrap()
+                .localizable;
+
+        assert_eq!(localizable.empty_source_values(), vec!["empty".to_string()]);
+    }
+
+    #[test]
+    fn html_review() {
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "greeting" : { "comment" : "Shown on <home>", "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Hi & welcome" } },
+      "nl" : { "stringUnit" : { "state" : "translated", "value" : "Hoi" } }
+    } },
+    "title" : { "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Title" } }
+    } }
+  },
+  "version" : "1.0"
+}"#;
+        let html = parse_from_string(raw.to_string())
+            .unwrap()
+            .localizable
+            .to_html_review();
+
+        assert!(html.contains("<tr><th>key</th><th>comment</th><th>en</th><th>nl</th></tr>"));
+        assert!(html.contains(
+            "<tr><td>greeting</td><td>Shown on &lt;home&gt;</td><td>Hi &amp; welcome</td><td>Hoi</td></tr>"
+        ));
+        assert!(html
+            .contains("<tr><td>title</td><td></td><td>Title</td><td class=\"missing\"></td></tr>"));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn max_concurrency() {
+        let dir = std::env::temp_dir().join("localizable_max_concurrency");
+
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let languages = ["de", "en", "fr", "ja", "nl"];
+        let localizations: Vec<_> = languages
+            .iter()
+            .map(|language| {
+                format!(
+                    r#""{language}" : {{ "stringUnit" : {{ "state" : "translated", "value" : "Title {language}" }} }}"#
+                )
+            })
+            .collect();
+        let raw = format!(
+            r#"{{ "sourceLanguage" : "en", "strings" : {{ "title" : {{ "localizations" : {{ {} }} }} }}, "version" : "1.0" }}"#,
+            localizations.join(", ")
+        );
+        let localized = parse_from_string(raw)
             .unwrap()
             .localizable
             .localized_per_language()
             .localized_for_android(AndroidLocalizeConfig {
                 write_config: Some(AndroidWriteConfig {
-                    write_in: current,
-                    only_write_language_code: None,
+                    write_in: dir.clone(),
+                    max_concurrency: Some(2),
+                    ..Default::default()
                 }),
                 ..Default::default()
-            });
+            })
+            .unwrap();
+
+        assert_eq!(
+            localized
+                .written_xmls
+                .iter()
+                .map(|written| written.language_code.as_str())
+                .collect::<Vec<_>>(),
+            languages.to_vec()
+        );
+
+        for written in &localized.written_xmls {
+            let content =
+                std::fs::read_to_string(dir.join(&written.sub_dir).join("strings.xml")).unwrap();
+
+            assert_eq!(localized.sorted_languages[&written.language_code], content);
+            assert!(content.contains(&format!("Title {}", written.language_code)));
+        }
     }
 
     #[test]
-    fn it_works() {
-        let raw = include_bytes!("../test_resources/Localizable.xcstrings");
-        let android_expected_en = include_bytes!("../test_resources/values/strings.xml");
-        let android_expected_nl = include_bytes!("../test_resources/values-nl/strings.xml");
-        let parsed = parse_from_bytes(raw).unwrap();
-        let localized_per_language = parsed.localizable.localized_per_language();
-        let android_actual = localized_per_language
+    fn keys_with_placeholders() {
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "discount" : { "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "100%% off" } }
+    } },
+    "greeting" : { "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Hi %@" } }
+    } },
+    "items" : { "localizations" : {
+      "en" : { "variations" : { "plural" : {
+        "one" : { "stringUnit" : { "state" : "translated", "value" : "One item" } },
+        "other" : { "stringUnit" : { "state" : "translated", "value" : "%lld items" } }
+      } } }
+    } },
+    "pages" : { "localizations" : {
+      "en" : { "variations" : { "plural" : {
+        "one" : { "stringUnit" : { "state" : "translated", "value" : "A page" } },
+        "other" : { "stringUnit" : { "state" : "translated", "value" : "Pages" } }
+      } } }
+    } },
+    "title" : { "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Title" } },
+      "nl" : { "stringUnit" : { "state" : "translated", "value" : "Titel %@" } }
+    } }
+  },
+  "version" : "1.0"
+}"#;
+        let localizable = parse_from_string(raw.to_string()).unwrap().localizable;
+
+        assert_eq!(
+            localizable.keys_with_placeholders(),
+            vec!["greeting".to_string(), "items".to_string()]
+        );
+    }
+
+    #[test]
+    fn translation_visitor() {
+        use crate::types::output::{SinglePluralVariation, SingleTranslation};
+        use crate::visit::TranslationVisitor;
+
+        #[derive(Default)]
+        struct PluralCaseCounter {
+            cases: usize,
+            keys: Vec<String>,
+        }
+
+        impl TranslationVisitor for PluralCaseCounter {
+            fn visit_plural(
+                &mut self,
+                single_translation: &SingleTranslation,
+                language: &str,
+                cases: &[SinglePluralVariation],
+            ) {
+                self.cases += cases.len();
+                self.keys
+                    .push(format!("{} ({language})", single_translation.key_raw));
+            }
+        }
+
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "items" : { "localizations" : {
+      "en" : { "variations" : { "plural" : {
+        "one" : { "stringUnit" : { "state" : "translated", "value" : "One item" } },
+        "other" : { "stringUnit" : { "state" : "translated", "value" : "%lld items" } }
+      } } },
+      "pl" : { "variations" : { "plural" : {
+        "one" : { "stringUnit" : { "state" : "translated", "value" : "Jeden" } },
+        "few" : { "stringUnit" : { "state" : "translated", "value" : "%lld elementy" } },
+        "many" : { "stringUnit" : { "state" : "translated", "value" : "%lld elementów" } }
+      } } }
+    } },
+    "title" : { "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Title" } }
+    } }
+  },
+  "version" : "1.0"
+}"#;
+        let mut counter = PluralCaseCounter::default();
+
+        parse_from_string(raw.to_string())
+            .unwrap()
+            .localizable
+            .accept(&mut counter);
+
+        assert_eq!(counter.cases, 5);
+        assert_eq!(counter.keys, vec!["items (en)", "items (pl)"]);
+    }
+
+    #[test]
+    fn bidi_pseudolocale() {
+        use crate::pseudo::PseudoLocale;
+
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "greeting" : { "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Hello %1$@, %#@files@ at 100%%" } }
+    } }
+  },
+  "version" : "1.0"
+}"#;
+        let mut localizable = parse_from_string(raw.to_string()).unwrap().localizable;
+
+        localizable.add_pseudolocale(PseudoLocale::Bidi);
+
+        let crate::types::output::Translation::Localization(l) = localizable.single_translation[0]
+            .translation_for("ar-XB")
+            .unwrap()
+        else {
+            panic!("Expected a localization");
+        };
+
+        assert_eq!( l.value, "]\u{200F}\u{202E}Hello\u{202C}\u{200F} %1$@\u{200F}\u{202E},\u{202C}\u{200F} %#@files@ \u{200F}\u{202E}at\u{202C}\u{200F} \u{200F}\u{202E}100\u{202C}\u{200F}%%[");
+
+        let localized = localizable
+            .localized_per_language()
             .localized_for_android(Default::default())
+            .unwrap();
+        let xml = &localized.sorted_languages["ar-XB"];
+
+        assert!(xml.contains('\u{202E}'));
+        assert!(xml.contains("%1$s"));
+        assert_eq!(
+            DirectoryCasing::Android.values_directory("ar-XB", "en"),
+            "values-ar-rXB"
+        );
+    }
+
+    #[test]
+    fn input_for_key() {
+        let parsed =
+            parse_from_bytes(include_bytes!("../test_resources/Comments.xcstrings")).unwrap();
+        let input = parsed.input_for_key("save").unwrap();
+
+        assert_eq!(input.comment, "Shown on the save button");
+        assert!(input.localizations.contains_key("en"));
+        assert!(parsed.input_for_key("missing").is_none());
+    }
+
+    #[test]
+    fn multiline_translations() {
+        let localizable = parse_from_bytes(include_bytes!("../test_resources/MultiLine.xcstrings"))
             .unwrap()
-            .sorted_languages;
+            .localizable;
 
-        for (language, value) in android_actual {
-            let expect = if &language == &parsed.localizable.source_language {
-                android_expected_en.to_vec()
-            } else {
-                assert_eq!(language, "nl");
+        assert_eq!(
+            localizable.multiline_translations(2),
+            vec![crate::validate::MultilineTranslation {
+                key_raw: "address".to_string(),
+                language: "nl".to_string(),
+                line_count: 3,
+            }]
+        );
+    }
 
-                android_expected_nl.to_vec()
-            };
+    #[test]
+    fn keys_by_word_count() {
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "intro" : { "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Welcome to the app, let's get started" } }
+    } },
+    "ok" : { "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "OK" } }
+    } },
+    "save" : { "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Save" } }
+    } }
+  },
+  "version" : "1.0"
+}"#;
+        let localizable = parse_from_string(raw.to_string()).unwrap().localizable;
 
-            let expect = String::from_utf8(expect).unwrap();
+        assert_eq!(
+            localizable.keys_by_word_count(),
+            vec![
+                ("intro".to_string(), 7),
+                ("ok".to_string(), 1),
+                ("save".to_string(), 1)
+            ]
+        );
+    }
 
-            assert_eq!(value.trim(), expect.trim());
+    #[test]
+    fn to_pot() {
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "Say \"hi\"" : { "comment" : "Greeting", "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Say \"hi\"" } },
+      "nl" : { "stringUnit" : { "state" : "translated", "value" : "Zeg hoi" } }
+    } },
+    "items" : { "localizations" : {
+      "en" : { "variations" : { "plural" : {
+        "one" : { "stringUnit" : { "state" : "translated", "value" : "One item" } },
+        "other" : { "stringUnit" : { "state" : "translated", "value" : "%lld items" } }
+      } } }
+    } }
+  },
+  "version" : "1.0"
+}"#;
+        let pot = parse_from_string(raw.to_string())
+            .unwrap()
+            .localizable
+            .to_pot();
+
+        assert_eq!(
+            pot,
+            r#"msgid ""
+msgstr ""
+"Content-Type: text/plain; charset=UTF-8\n"
+
+#. Greeting
+msgid "Say \"hi\""
+msgstr ""
+
+msgctxt "items"
+msgid "One item"
+msgid_plural "%lld items"
+msgstr[0] ""
+msgstr[1] ""
+"#
+        );
+        assert!(!pot.contains("Zeg hoi"));
+    }
+
+    #[test]
+    fn max_consecutive_newlines() {
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "intro" : { "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Welcome\n\n\nto the app\nenjoy" } }
+    } }
+  },
+  "version" : "1.0"
+}"#;
+        let value = |config: &ParseConfig| {
+            parse_from_bytes_with_config(raw.as_bytes(), config)
+                .unwrap()
+                .localizable
+                .single_translation[0]
+                .translation_for("en")
+                .unwrap()
+                .translation_values()[0]
+                .value
+                .to_string()
+        };
+
+        assert_eq!(
+            value(&ParseConfig::default()),
+            "Welcome\n\n\nto the app\nenjoy"
+        );
+        assert_eq!(
+            value(&ParseConfig {
+                max_consecutive_newlines: Some(1),
+                ..Default::default()
+            }),
+            "Welcome\nto the app\nenjoy"
+        );
+        assert_eq!(
+            value(&ParseConfig {
+                max_consecutive_newlines: Some(2),
+                ..Default::default()
+            }),
+            "Welcome\n\nto the app\nenjoy"
+        );
+    }
+
+    #[test]
+    fn plural_count_omissions() {
+        let localizable =
+            parse_from_bytes(include_bytes!("../test_resources/PluralCount.xcstrings"))
+                .unwrap()
+                .localizable;
+
+        assert_eq!(
+            localizable.plural_count_omissions(crate::validate::DEFAULT_COUNT_REQUIRED),
+            vec![crate::validate::PluralCountOmission {
+                key_raw: "items".to_string(),
+                language: "en".to_string(),
+                variate: PluralVariate::Other,
+            }]
+        );
+    }
+
+    #[test]
+    fn to_tmx() {
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "terms" : { "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Terms & conditions" } },
+      "nl" : { "stringUnit" : { "state" : "translated", "value" : "Voorwaarden" } }
+    } },
+    "items" : { "localizations" : {
+      "en" : { "variations" : { "plural" : {
+        "one" : { "stringUnit" : { "state" : "translated", "value" : "One item" } },
+        "other" : { "stringUnit" : { "state" : "translated", "value" : "%lld items" } }
+      } } }
+    } }
+  },
+  "version" : "1.0"
+}"#;
+        let tmx = parse_from_string(raw.to_string())
+            .unwrap()
+            .localizable
+            .to_tmx();
+
+        assert!(tmx.contains(
+            "<tu tuid=\"terms\">\n<tuv xml:lang=\"en\"><seg>Terms &amp; conditions</seg></tuv>\n<tuv xml:lang=\"nl\"><seg>Voorwaarden</seg></tuv>\n</tu>"
+        ));
+        assert!(tmx.contains(
+            "<tu tuid=\"items[other]\">\n<tuv xml:lang=\"en\"><seg>%lld items</seg></tuv>\n</tu>"
+        ));
+        assert!(tmx.contains("srclang=\"en\""));
+    }
+
+    #[test]
+    fn device_variations() {
+        use crate::types::output::{DeviceVariate, Translation};
+
+        let localizable = parse_from_bytes(include_bytes!(
+            "../test_resources/DeviceVariations.xcstrings"
+        ))
+        .unwrap()
+        .localizable;
+        let translation = localizable.single_translation[0]
+            .translation_for("en")
+            .unwrap();
+        let Translation::DeviceVariation(devices) = translation else {
+            panic!("Expected a device variation");
+        };
+
+        assert_eq!(
+            devices
+                .iter()
+                .map(|single| single.variate)
+                .collect::<Vec<_>>(),
+            vec![
+                DeviceVariate::IPhone,
+                DeviceVariate::Mac,
+                DeviceVariate::Other
+            ]
+        );
+        assert_eq!(
+            translation.device_fallback().unwrap().value,
+            "Select to continue"
+        );
+
+        let localized = localizable
+            .localized_per_language()
+            .localized_for_android(Default::default())
+            .unwrap();
+
+        assert!(localized.sorted_languages["en"]
+            .contains("<string name=\"tap_to_continue\">Select to continue</string>"));
+        // Without `other` the first device is used
+        assert!(localized.sorted_languages["nl"]
+            .contains("<string name=\"tap_to_continue\">Tik om door te gaan</string>"));
+
+        let reparsed = parse_from_string(localizable.to_xcstrings())
+            .unwrap()
+            .localizable;
+
+        assert_eq!(
+            localizable.single_translation[0].localization_value,
+            reparsed.single_translation[0].localization_value
+        );
+    }
+
+    #[test]
+    fn validate_each() {
+        use crate::validate::{TypographyChecks, ValidationConfig, ValidationIssue};
+
+        let mut localizable =
+            parse_from_bytes(include_bytes!("../test_resources/Typography.xcstrings"))
+                .unwrap()
+                .localizable;
+
+        localizable.merge(
+            &parse_from_bytes(include_bytes!("../test_resources/MultiLine.xcstrings"))
+                .unwrap()
+                .localizable,
+            crate::merge::MergeStrategy::Overwrite,
+        );
+
+        let config = ValidationConfig {
+            max_lines: Some(2),
+            ..Default::default()
+        };
+        let mut streamed = vec![];
+
+        localizable.validate_each(&config, &mut |issue| streamed.push(issue));
+
+        let collected: Vec<_> = localizable
+            .control_characters()
+            .into_iter()
+            .map(ValidationIssue::ControlCharacter)
+            .chain(
+                localizable
+                    .oversized_translations(crate::validate::DEFAULT_MAX_TRANSLATION_CHARS)
+                    .into_iter()
+                    .map(ValidationIssue::Oversized),
+            )
+            .chain(
+                localizable
+                    .multiline_translations(2)
+                    .into_iter()
+                    .map(ValidationIssue::Multiline),
+            )
+            .chain(
+                localizable
+                    .typography_issues(TypographyChecks::default())
+                    .into_iter()
+                    .map(ValidationIssue::Typography),
+            )
+            .chain(
+                localizable
+                    .plural_count_omissions(crate::validate::DEFAULT_COUNT_REQUIRED)
+                    .into_iter()
+                    .map(ValidationIssue::PluralCountOmission),
+            )
+            .collect();
+
+        assert_eq!(streamed.len(), 5);
+        assert_eq!(collected, streamed);
+
+        let raw = r#"{
+            "sourceLanguage": "en",
+            "strings": {
+                "1_invite": { "localizations": {
+                    "en": { "stringUnit": { "state": "translated", "value": "%1$@ invited %2$@." } },
+                    "nl": { "stringUnit": { "state": "translated", "value": "%2$@ is uitgenodigd" } }
+                } }
+            },
+            "version": "1.0"
+        }"#;
+        let mut streamed = vec![];
+
+        parse_from_string(raw.to_string())
+            .unwrap()
+            .localizable
+            .validate_each(&Default::default(), &mut |issue| streamed.push(issue));
+
+        assert_eq!(streamed.len(), 3);
+        assert!(matches!(&streamed[0], ValidationIssue::Position(issue) if issue.language == "nl"));
+        assert!(matches!(
+            &streamed[1],
+            ValidationIssue::TrailingPunctuation(issue) if issue.key_raw == "1_invite"
+        ));
+        assert_eq!(
+            streamed[2],
+            ValidationIssue::DigitLeadingAndroidKey("1_invite".to_string())
+        );
+    }
+
+    #[test]
+    fn substitutions() {
+        let localizable =
+            parse_from_bytes(include_bytes!("../test_resources/Substitutions.xcstrings"))
+                .unwrap()
+                .localizable;
+        let substitutions = &localizable.single_translation[0].substitutions["en"];
+
+        assert_eq!(
+            substitutions.keys().collect::<Vec<_>>(),
+            vec!["files", "folders"]
+        );
+        assert_eq!(substitutions["folders"].arg_num, 2);
+        assert_eq!(substitutions["folders"].specifier(), "%2$lld");
+        assert_eq!(
+            substitutions["files"]
+                .plural
+                .iter()
+                .find(|single| single.variate == PluralVariate::Other)
+                .unwrap()
+                .translation_value
+                .value,
+            "%arg files"
+        );
+
+        let localized = localizable
+            .localized_per_language()
+            .localized_for_android(Default::default())
+            .unwrap();
+
+        assert!(localized.sorted_languages["en"].contains(
+            "<string name=\"files_in_folders\">Found %1$d files in %2$d folders</string>"
+        ));
+
+        let mut unresolved = localizable.clone();
+
+        unresolved.single_translation[0].substitutions.clear();
+
+        let localized = unresolved
+            .localized_per_language()
+            .localized_for_android(Default::default())
+            .unwrap();
+
+        assert!(localized.sorted_languages["en"].contains("Found %#@files@ in %#@folders@"));
+        assert_eq!(specifier::to_android("Found %#@files@"), "Found %#@files@");
+
+        let reparsed = parse_from_string(localizable.to_xcstrings())
+            .unwrap()
+            .localizable;
+
+        assert_eq!(
+            localizable.single_translation[0].substitutions,
+            reparsed.single_translation[0].substitutions
+        );
+    }
+
+    #[test]
+    fn seed_from_glossary() {
+        let mut localizable =
+            parse_from_bytes(include_bytes!("../test_resources/Comments.xcstrings"))
+                .unwrap()
+                .localizable;
+        let glossary = BTreeMap::from([
+            ("Save".to_string(), "Opslaan".to_string()),
+            ("Unused".to_string(), "Ongebruikt".to_string()),
+        ]);
+
+        assert_eq!(
+            localizable.seed_from_glossary("nl", &glossary),
+            vec!["save"]
+        );
+
+        let save = localizable
+            .single_translation
+            .iter()
+            .find(|st| st.key_raw == "save")
+            .unwrap();
+        let Some(crate::types::output::Translation::Localization(seeded)) =
+            save.translation_for("nl")
+        else {
+            panic!("expected a seeded localization");
+        };
+
+        assert_eq!(seeded.value, "Opslaan");
+        assert_eq!(seeded.state, NEEDS_REVIEW_STATE);
+        assert!(localizable
+            .single_translation
+            .iter()
+            .filter(|st| st.key_raw != "save")
+            .all(|st| st.translation_for("nl").is_none()));
+        assert!(localizable.seed_from_glossary("nl", &glossary).is_empty());
+
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "copied" : { "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Save" } },
+      "nl" : { "stringUnit" : { "state" : "new", "value" : "Save" } }
+    } },
+    "reviewed" : { "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Save" } },
+      "nl" : { "stringUnit" : { "state" : "translated", "value" : "Bewaren" } }
+    } }
+  },
+  "version" : "1.0"
+}"#;
+        let mut localizable = parse_from_string(raw.to_string()).unwrap().localizable;
+
+        assert_eq!(
+            localizable.seed_from_glossary("nl", &glossary),
+            vec!["copied"]
+        );
+        assert_eq!(
+            localizable.single_translation[1]
+                .translation_for("nl")
+                .unwrap()
+                .display_value(),
+            "Bewaren"
+        );
+    }
+
+    #[test]
+    fn emit_non_translatable() {
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "brand" : { "shouldTranslate" : false, "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Acme" } },
+      "nl" : { "stringUnit" : { "state" : "translated", "value" : "Acme" } }
+    } },
+    "title" : { "localizations" : {
+      "en" : { "stringUnit" : { "state" : "translated", "value" : "Title" } },
+      "nl" : { "stringUnit" : { "state" : "translated", "value" : "Titel" } }
+    } }
+  },
+  "version" : "1.0"
+}"#;
+        let localized_per_language = parse_from_string(raw.to_string())
+            .unwrap()
+            .localizable
+            .localized_per_language();
+        let skipped = localized_per_language
+            .localized_for_android(Default::default())
+            .unwrap();
+
+        for xml in skipped.sorted_languages.values() {
+            assert!(!xml.contains("brand"));
         }
+
+        assert!(skipped.sorted_languages["nl"].contains("Titel"));
+
+        let emitted = localized_per_language
+            .localized_for_android(AndroidLocalizeConfig {
+                emit_non_translatable: true,
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert!(emitted.sorted_languages["en"]
+            .contains("<string name=\"brand\" translatable=\"false\">Acme</string>"));
+        assert!(!emitted.sorted_languages["nl"].contains("brand"));
+    }
+
+    #[test]
+    fn unsupported_variations() {
+        let nested = parse_from_bytes(include_bytes!(
+            "../test_resources/NestedDeviceVariations.xcstrings"
+        ))
+        .unwrap_err();
+
+        assert!(matches!(
+            nested,
+            ParsedError::UnsupportedVariation(error)
+                if error == "items_selected (en): a variation per device"
+        ));
+
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "empty" : { "localizations" : { "en" : { "variations" : {} } } }
+  },
+  "version" : "1.0"
+}"#;
+
+        assert!(matches!(
+            parse_from_string(raw.to_string()).unwrap_err(),
+            ParsedError::UnsupportedVariation(error)
+                if error == "empty (en): no plural or device variation"
+        ));
+
+        let raw = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "empty" : { "localizations" : { "en" : { "variations" : { "device" : {} } } } }
+  },
+  "version" : "1.0"
+}"#;
+
+        assert!(matches!(
+            parse_from_string(raw.to_string()).unwrap_err(),
+            ParsedError::UnsupportedVariation(error)
+                if error == "empty (en): an empty device variation"
+        ));
+    }
+
+    #[test]
+    fn completion_percent() {
+        let raw = r#"{
+            "sourceLanguage": "en",
+            "strings": {
+                "a": { "localizations": {
+                    "en": { "stringUnit": { "state": "translated", "value": "A" } },
+                    "nl": { "stringUnit": { "state": "translated", "value": "A" } }
+                } },
+                "b": { "localizations": {
+                    "en": { "stringUnit": { "state": "translated", "value": "B" } },
+                    "nl": { "stringUnit": { "state": "translated", "value": "B" } }
+                } }
+            },
+            "version": "1.0"
+        }"#;
+        let mut localizable = parse_from_string(raw.to_string()).unwrap().localizable;
+
+        localizable.single_translation[1]
+            .localization_value
+            .language_translation
+            .remove("en");
+
+        assert_eq!(localizable.completion_percent("nl"), 100.0);
+    }
+
+    #[test]
+    fn file_groups_empty_primary_file() {
+        let raw = r#"{
+            "sourceLanguage": "en",
+            "strings": {
+                "onboarding.title": { "localizations": {
+                    "en": { "stringUnit": { "state": "translated", "value": "Welcome" } }
+                } }
+            },
+            "version": "1.0"
+        }"#;
+        let localized = parse_from_string(raw.to_string())
+            .unwrap()
+            .localizable
+            .localized_per_language()
+            .localized_for_android(AndroidLocalizeConfig {
+                file_groups: [("onboarding.".to_string(), "strings_onboarding".to_string())]
+                    .into_iter()
+                    .collect(),
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert_eq!(
+            localized.sorted_languages["en"],
+            "<resources>\n\n</resources>"
+        );
+        assert!(localized.grouped_languages["en"]["strings_onboarding.xml"].contains("Welcome"));
     }
 }